@@ -0,0 +1,309 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! ASN.1 object identifiers for the hash codecs and DER encoding and decoding
+//! of the PKCS#1 (RFC 8017) `DigestInfo` structure:
+//!
+//! ```text
+//! DigestInfo ::= SEQUENCE {
+//!     digestAlgorithm AlgorithmIdentifier,
+//!     digest OCTET STRING
+//! }
+//! ```
+use crate::{Error, Multihash};
+use multicodec::Codec;
+
+/// DER tag for a SEQUENCE
+const TAG_SEQUENCE: u8 = 0x30;
+/// DER tag for an OBJECT IDENTIFIER
+const TAG_OID: u8 = 0x06;
+/// DER tag for NULL
+const TAG_NULL: u8 = 0x05;
+/// DER tag for an OCTET STRING
+const TAG_OCTET_STRING: u8 = 0x04;
+
+/// the algorithm object identifiers of the hash codecs. blake3 and
+/// ripemd-320 don't have registered object identifiers
+pub const HASH_OIDS: [(Codec, &str); 21] = [
+    (Codec::Blake2B224, "1.3.6.1.4.1.1722.12.2.1.7"),
+    (Codec::Blake2B256, "1.3.6.1.4.1.1722.12.2.1.8"),
+    (Codec::Blake2B384, "1.3.6.1.4.1.1722.12.2.1.12"),
+    (Codec::Blake2B512, "1.3.6.1.4.1.1722.12.2.1.16"),
+    (Codec::Blake2S224, "1.3.6.1.4.1.1722.12.2.2.7"),
+    (Codec::Blake2S256, "1.3.6.1.4.1.1722.12.2.2.8"),
+    (Codec::Md5, "1.2.840.113549.2.5"),
+    (Codec::Ripemd128, "1.3.36.3.2.2"),
+    (Codec::Ripemd160, "1.3.36.3.2.1"),
+    (Codec::Ripemd256, "1.3.36.3.2.3"),
+    (Codec::Sha1, "1.3.14.3.2.26"),
+    (Codec::Sha2224, "2.16.840.1.101.3.4.2.4"),
+    (Codec::Sha2256, "2.16.840.1.101.3.4.2.1"),
+    (Codec::Sha2384, "2.16.840.1.101.3.4.2.2"),
+    (Codec::Sha2512, "2.16.840.1.101.3.4.2.3"),
+    (Codec::Sha2512224, "2.16.840.1.101.3.4.2.5"),
+    (Codec::Sha2512256, "2.16.840.1.101.3.4.2.6"),
+    (Codec::Sha3224, "2.16.840.1.101.3.4.2.7"),
+    (Codec::Sha3256, "2.16.840.1.101.3.4.2.8"),
+    (Codec::Sha3384, "2.16.840.1.101.3.4.2.9"),
+    (Codec::Sha3512, "2.16.840.1.101.3.4.2.10"),
+];
+
+/// the dotted object identifier of the hash codec
+pub fn oid(codec: Codec) -> Option<&'static str> {
+    HASH_OIDS.iter().find(|(c, _)| *c == codec).map(|(_, o)| *o)
+}
+
+/// the hash codec of the dotted object identifier
+pub fn oid_codec(oid: &str) -> Option<Codec> {
+    HASH_OIDS.iter().find(|(_, o)| *o == oid).map(|(c, _)| *c)
+}
+
+/// the DER contents of a dotted object identifier
+fn encode_oid(oid: &str) -> Vec<u8> {
+    let arcs: Vec<u64> = oid.split('.').filter_map(|a| a.parse().ok()).collect();
+    let mut v = Vec::default();
+    // the first two arcs are combined into one
+    let first = arcs.first().copied().unwrap_or(0) * 40 + arcs.get(1).copied().unwrap_or(0);
+    for arc in std::iter::once(first).chain(arcs.iter().skip(2).copied()) {
+        // base 128, most significant group first, high bit set on all but the last
+        let mut groups = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            groups.push((rest & 0x7f) as u8 | 0x80);
+            rest >>= 7;
+        }
+        v.extend(groups.iter().rev());
+    }
+    v
+}
+
+/// the dotted form of the DER contents of an object identifier
+fn decode_oid(bytes: &[u8]) -> Result<String, Error> {
+    let invalid = || Error::InvalidDigestInfo("invalid object identifier".to_string());
+    let mut arcs: Vec<u64> = Vec::default();
+    let mut arc: u64 = 0;
+    for (i, b) in bytes.iter().enumerate() {
+        // groups must be minimal and arcs must fit in a u64
+        if arc == 0 && *b == 0x80 || arc > u64::MAX >> 7 {
+            return Err(invalid());
+        }
+        arc = (arc << 7) | u64::from(b & 0x7f);
+        if b & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = (arc / 40).min(2);
+                arcs.push(first);
+                arcs.push(arc - first * 40);
+            } else {
+                arcs.push(arc);
+            }
+            arc = 0;
+        } else if i == bytes.len() - 1 {
+            return Err(invalid());
+        }
+    }
+    if arcs.is_empty() {
+        return Err(invalid());
+    }
+    Ok(arcs
+        .iter()
+        .map(|a| a.to_string())
+        .collect::<Vec<_>>()
+        .join("."))
+}
+
+/// append a DER tag, length and contents
+fn write_tlv(v: &mut Vec<u8>, tag: u8, contents: &[u8]) {
+    v.push(tag);
+    let len = contents.len();
+    if len < 0x80 {
+        v.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let skip = bytes.iter().take_while(|b| **b == 0).count();
+        v.push(0x80 | (bytes.len() - skip) as u8);
+        v.extend_from_slice(&bytes[skip..]);
+    }
+    v.extend_from_slice(contents);
+}
+
+/// read a DER value with the expected tag, returning its contents and the
+/// rest of the input
+fn read_tlv(bytes: &[u8], tag: u8) -> Result<(&[u8], &[u8]), Error> {
+    let invalid = |msg: &str| Error::InvalidDigestInfo(msg.to_string());
+    let (t, rest) = bytes.split_first().ok_or_else(|| invalid("truncated"))?;
+    if *t != tag {
+        return Err(Error::InvalidDigestInfo(format!(
+            "expected tag 0x{:02x}, found 0x{:02x}",
+            tag, t
+        )));
+    }
+    let (l, mut rest) = rest.split_first().ok_or_else(|| invalid("truncated"))?;
+    let len = if *l < 0x80 {
+        *l as usize
+    } else {
+        // long form lengths must be minimal
+        let n = (*l & 0x7f) as usize;
+        if n == 0 || n > size_of::<usize>() || rest.len() < n || rest[0] == 0 {
+            return Err(invalid("invalid length"));
+        }
+        let len = rest[..n]
+            .iter()
+            .fold(0usize, |len, b| (len << 8) | *b as usize);
+        if len < 0x80 {
+            return Err(invalid("invalid length"));
+        }
+        rest = &rest[n..];
+        len
+    };
+    if rest.len() < len {
+        return Err(invalid("truncated"));
+    }
+    Ok(rest.split_at(len))
+}
+
+impl Multihash {
+    /// the DER encoded PKCS#1 `DigestInfo` of this multihash. the algorithm
+    /// parameters are NULL as PKCS#1 requires. the OID names the full hash so
+    /// a truncated digest is an error
+    pub fn to_digest_info(&self) -> Result<Vec<u8>, Error> {
+        let oid = oid(self.codec).ok_or(Error::UnsupportedHash(self.codec))?;
+        self.check_full_digest()?;
+        let mut alg = Vec::default();
+        write_tlv(&mut alg, TAG_OID, &encode_oid(oid));
+        write_tlv(&mut alg, TAG_NULL, &[]);
+        let mut info = Vec::default();
+        write_tlv(&mut info, TAG_SEQUENCE, &alg);
+        write_tlv(&mut info, TAG_OCTET_STRING, &self.hash);
+        let mut v = Vec::default();
+        write_tlv(&mut v, TAG_SEQUENCE, &info);
+        Ok(v)
+    }
+
+    /// decode a DER encoded PKCS#1 `DigestInfo`. the algorithm parameters may
+    /// be NULL or absent
+    pub fn from_digest_info(bytes: &[u8]) -> Result<Self, Error> {
+        let (info, rest) = read_tlv(bytes, TAG_SEQUENCE)?;
+        if !rest.is_empty() {
+            return Err(Error::TrailingData {
+                offset: bytes.len() - rest.len(),
+                len: rest.len(),
+            });
+        }
+        let (alg, info) = read_tlv(info, TAG_SEQUENCE)?;
+        let (digest, info) = read_tlv(info, TAG_OCTET_STRING)?;
+        if !info.is_empty() {
+            return Err(Error::InvalidDigestInfo("extra fields".to_string()));
+        }
+        let (oid, params) = read_tlv(alg, TAG_OID)?;
+        if !params.is_empty() && params != [TAG_NULL, 0] {
+            return Err(Error::InvalidDigestInfo("invalid parameters".to_string()));
+        }
+        let oid = decode_oid(oid)?;
+        let codec = oid_codec(&oid)
+            .ok_or_else(|| Error::InvalidDigestInfo(format!("unknown algorithm {}", oid)))?;
+        Self::from_digest(codec, digest.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mh::HASH_CODECS;
+
+    const DATA: &[u8] = b"for great justice, move every zig!";
+
+    #[test]
+    fn test_oids() {
+        for codec in HASH_CODECS {
+            match oid(codec) {
+                Some(o) => {
+                    assert_eq!(oid_codec(o), Some(codec));
+                    assert_eq!(decode_oid(&encode_oid(o)).unwrap(), o);
+                }
+                None => assert!(matches!(codec, Codec::Blake3 | Codec::Ripemd320)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_digest_info_prefixes() {
+        // the DigestInfo prefixes from RFC 8017 section 9.2
+        for (codec, prefix) in [
+            (Codec::Md5, "3020300c06082a864886f70d020505000410"),
+            (Codec::Sha1, "3021300906052b0e03021a05000414"),
+            (Codec::Sha2256, "3031300d060960864801650304020105000420"),
+            (Codec::Sha2512, "3051300d060960864801650304020305000440"),
+        ] {
+            let mh = Multihash::of_bytes(codec, DATA).unwrap();
+            let der = mh.to_digest_info().unwrap();
+            assert_eq!(hex::encode(&der[..der.len() - mh.as_ref().len()]), prefix);
+            assert_eq!(Multihash::from_digest_info(&der).unwrap(), mh);
+        }
+    }
+
+    #[test]
+    fn test_digest_info_truncated() {
+        // a sha1 DigestInfo always has a 20 byte digest after its prefix
+        let mh = Multihash::of_bytes(Codec::Sha1, DATA).unwrap();
+        let truncated = Multihash {
+            codec: mh.codec,
+            hash: mh.as_ref()[..10].to_vec(),
+            encoding: None,
+        };
+        assert_eq!(
+            truncated.to_digest_info(),
+            Err(Error::InvalidDigestLength {
+                expected: 20,
+                len: 10
+            })
+        );
+    }
+
+    #[test]
+    fn test_digest_info_roundtrip() {
+        for (codec, _) in HASH_OIDS {
+            let mh = Multihash::of_bytes(codec, DATA).unwrap();
+            let der = mh.to_digest_info().unwrap();
+            assert_eq!(Multihash::from_digest_info(&der).unwrap(), mh);
+        }
+    }
+
+    #[test]
+    fn test_digest_info_errors() {
+        let mh = Multihash::of_bytes(Codec::Blake3, DATA).unwrap();
+        assert_eq!(
+            mh.to_digest_info(),
+            Err(Error::UnsupportedHash(Codec::Blake3))
+        );
+
+        // absent parameters are accepted
+        let sha256 = hex::decode("302f300b0609608648016503040201").unwrap();
+        let mh = Multihash::of_bytes(Codec::Sha2256, DATA).unwrap();
+        let mut der = sha256.clone();
+        der.extend_from_slice(&[0x04, 0x20]);
+        der.extend_from_slice(mh.as_ref());
+        assert_eq!(Multihash::from_digest_info(&der).unwrap(), mh);
+
+        // truncated and trailing data
+        assert!(matches!(
+            Multihash::from_digest_info(&der[..der.len() - 1]),
+            Err(Error::InvalidDigestInfo(_))
+        ));
+        let mut t = der.clone();
+        t.push(0);
+        assert_eq!(
+            Multihash::from_digest_info(&t),
+            Err(Error::TrailingData {
+                offset: der.len(),
+                len: 1
+            })
+        );
+
+        // an unknown algorithm
+        let der = hex::decode("300d300906052b0e03021b05000400").unwrap();
+        assert_eq!(
+            Multihash::from_digest_info(&der),
+            Err(Error::InvalidDigestInfo(
+                "unknown algorithm 1.3.14.3.2.27".to_string()
+            ))
+        );
+    }
+}
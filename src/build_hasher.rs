@@ -0,0 +1,191 @@
+// SPDX-License-Idnetifier: Apache-2.0
+use crate::mh::SAFE_HASH_CODECS;
+use std::{
+    collections::hash_map::{DefaultHasher, RandomState},
+    hash::{BuildHasher, Hasher},
+};
+
+/// the marker the `Hash` impl of [`crate::Multihash`] writes before the codec
+/// so that [`MultihashHasher`] only treats the writes that follow it as a
+/// multihash and never an unrelated integer that happens to equal a codec
+pub(crate) const MULTIHASH_MARKER: u128 = u128::from_be_bytes(*b"multihash digest");
+
+/// A [`BuildHasher`] for maps and sets keyed by [`crate::Multihash`] that
+/// reuses the leading bytes of the digest as the hash value instead of hashing
+/// the digest a second time. This is only done for the cryptographic hash
+/// codecs in [`SAFE_HASH_CODECS`], everything else is hashed with the standard
+/// library's randomly keyed hasher.
+///
+/// The digest bytes are used as is so this gives up the resistance to hash
+/// flooding that the standard library's hasher has. Only use it when the keys
+/// are multihashes computed, or verified, locally and not chosen by someone
+/// else.
+///
+/// ```ignore
+/// let mut map: HashMap<Multihash, u64, MultihashBuildHasher> = HashMap::default();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MultihashBuildHasher {
+    random_state: RandomState,
+}
+
+impl BuildHasher for MultihashBuildHasher {
+    type Hasher = MultihashHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        MultihashHasher {
+            fallback: self.random_state.build_hasher(),
+            digest: None,
+            state: State::Idle,
+        }
+    }
+}
+
+/// how far into the writes of a multihash the hasher is
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum State {
+    /// not in the middle of a multihash
+    Idle,
+    /// the multihash marker was written
+    Marker,
+    /// the code of a safe hash codec followed the marker
+    Codec,
+    /// the digest length followed the codec, the digest is next
+    Length,
+}
+
+/// The [`Hasher`] created by [`MultihashBuildHasher`]. It follows the writes
+/// of the `Hash` impl of [`crate::Multihash`]: the marker with `write_u128`,
+/// the codec with `write_u64`, the digest length with `write_usize` and then
+/// the digest with a single `write`. Any other write in between starts over
+#[derive(Clone, Debug)]
+pub struct MultihashHasher {
+    /// hasher for everything that isn't a trusted digest
+    fallback: DefaultHasher,
+    /// the leading bytes of the first trusted digest written
+    digest: Option<u64>,
+    /// how far into the writes of a multihash the hasher is
+    state: State,
+}
+
+impl Hasher for MultihashHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        if self.state == State::Length && self.digest.is_none() && bytes.len() >= 8 {
+            let mut b = [0u8; 8];
+            b.copy_from_slice(&bytes[..8]);
+            self.digest = Some(u64::from_le_bytes(b));
+        } else {
+            self.fallback.write(bytes);
+        }
+        self.state = State::Idle;
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.fallback.write_u128(i);
+        self.state = if i == MULTIHASH_MARKER {
+            State::Marker
+        } else {
+            State::Idle
+        };
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.fallback.write_u64(i);
+        self.state =
+            if self.state == State::Marker && SAFE_HASH_CODECS.iter().any(|c| c.code() == i) {
+                State::Codec
+            } else {
+                State::Idle
+            };
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.fallback.write_usize(i);
+        self.state = if self.state == State::Codec {
+            State::Length
+        } else {
+            State::Idle
+        };
+    }
+
+    fn finish(&self) -> u64 {
+        self.digest.unwrap_or_else(|| self.fallback.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Multihash;
+    use multicodec::Codec;
+    use std::collections::HashMap;
+
+    fn hash_of(mh: &Multihash, bh: &MultihashBuildHasher) -> u64 {
+        bh.hash_one(mh)
+    }
+
+    fn leading_u64(bytes: &[u8]) -> u64 {
+        let mut b = [0u8; 8];
+        b.copy_from_slice(&bytes[..8]);
+        u64::from_le_bytes(b)
+    }
+
+    #[test]
+    fn test_digest_reused() {
+        let bh = MultihashBuildHasher::default();
+        for codec in SAFE_HASH_CODECS {
+            let mh = Multihash::of_bytes(codec, b"multihash").unwrap();
+            assert_eq!(hash_of(&mh, &bh), leading_u64(mh.as_ref()));
+        }
+    }
+
+    #[test]
+    fn test_unsafe_not_reused() {
+        let bh = MultihashBuildHasher::default();
+        let mh = Multihash {
+            codec: Codec::Identity,
+            hash: b"multihash".to_vec(),
+            encoding: None,
+        };
+        assert_ne!(hash_of(&mh, &bh), leading_u64(mh.as_ref()));
+        assert_eq!(hash_of(&mh, &bh), hash_of(&mh.clone(), &bh));
+
+        // md5, sha1 and sha2 aren't in the safe hash codecs
+        for codec in [Codec::Md5, Codec::Sha1, Codec::Sha2256] {
+            let mh = Multihash::of_bytes(codec, b"multihash").unwrap();
+            assert_ne!(hash_of(&mh, &bh), leading_u64(mh.as_ref()));
+        }
+    }
+
+    #[test]
+    fn test_unmarked_not_reused() {
+        let bh = MultihashBuildHasher::default();
+        let digest = [0x5a; 32];
+
+        // an integer equal to a codec followed by a length and some bytes
+        // isn't mistaken for a multihash without the marker
+        let mut h = bh.build_hasher();
+        h.write_u64(Codec::Blake3.code());
+        h.write_usize(digest.len());
+        h.write(&digest);
+        assert_ne!(h.finish(), leading_u64(&digest));
+    }
+
+    #[test]
+    fn test_map() {
+        let mut map: HashMap<Multihash, usize, MultihashBuildHasher> = HashMap::default();
+        let inputs: Vec<Vec<u8>> = (0..1000u32).map(|i| i.to_be_bytes().to_vec()).collect();
+        for (i, mh) in Multihash::of_all(Codec::Blake3, &inputs)
+            .unwrap()
+            .into_iter()
+            .enumerate()
+        {
+            map.insert(mh, i);
+        }
+        assert_eq!(map.len(), inputs.len());
+        for (i, input) in inputs.iter().enumerate() {
+            let mh = Multihash::of_bytes(Codec::Blake3, input).unwrap();
+            assert_eq!(map.get(&mh), Some(&i));
+        }
+    }
+}
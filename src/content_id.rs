@@ -0,0 +1,322 @@
+// SPDX-License-Idnetifier: Apache-2.0
+use crate::{mh::decode_codec, Error, Multihash};
+use core::{fmt, str::FromStr};
+use multibase::Base;
+use multicodec::Codec;
+use multitrait::TryDecodeFrom;
+use multiutil::{CodecInfo, EncodingInfo};
+
+/// the length of the binary encoding of a CIDv0
+const V0_LEN: usize = 34;
+
+/// the length of the base58btc string encoding of a CIDv0
+const V0_STR_LEN: usize = 46;
+
+/// The version of a [`Cid`]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Version {
+    /// a bare sha2-256 multihash of dag-pb content, always base58btc encoded
+    V0,
+    /// a version, content codec and multihash, multibase encoded
+    V1,
+}
+
+impl From<Version> for u64 {
+    fn from(v: Version) -> Self {
+        match v {
+            Version::V0 => 0,
+            Version::V1 => 1,
+        }
+    }
+}
+
+/// A content identifier made of a content codec, such as raw, dag-pb or
+/// dag-cbor, and the Multihash of the content
+#[derive(Clone, Debug)]
+pub struct Cid {
+    version: Version,
+    codec: Codec,
+    hash: Multihash,
+    encoding: Option<Base>,
+}
+
+impl Cid {
+    /// create a CIDv0, the multihash must be a sha2-256 hash of dag-pb content
+    pub fn new_v0(hash: Multihash) -> Result<Self, Error> {
+        if hash.codec() != Codec::Sha2256 || hash.as_ref().len() != 32 {
+            return Err(Error::InvalidCidV0);
+        }
+        Ok(Self {
+            version: Version::V0,
+            codec: Codec::DagPb,
+            hash,
+            encoding: None,
+        })
+    }
+
+    /// create a CIDv1 of content with the given codec
+    pub fn new_v1(codec: Codec, hash: Multihash) -> Self {
+        Self {
+            version: Version::V1,
+            codec,
+            hash,
+            encoding: None,
+        }
+    }
+
+    /// the version of this cid
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// the multihash of the content
+    pub fn hash(&self) -> &Multihash {
+        &self.hash
+    }
+
+    /// get back the multihash of the content
+    pub fn into_hash(self) -> Multihash {
+        self.hash
+    }
+
+    /// convert to a CIDv1 of the same content, CIDv1 values are unchanged
+    pub fn into_v1(self) -> Self {
+        match self.version {
+            Version::V0 => Self::new_v1(self.codec, self.hash),
+            Version::V1 => self,
+        }
+    }
+
+    /// convert to a CIDv0 of the same content, only dag-pb sha2-256 CIDv1
+    /// values can be converted
+    pub fn into_v0(self) -> Result<Self, Error> {
+        match self.version {
+            Version::V0 => Ok(self),
+            Version::V1 if self.codec == Codec::DagPb => Self::new_v0(self.hash),
+            Version::V1 => Err(Error::InvalidCidV0),
+        }
+    }
+
+    /// set the base encoding used when this CIDv1 is encoded as a string,
+    /// CIDv0 values are always base58btc encoded
+    pub fn with_encoding(mut self, base: Base) -> Self {
+        self.encoding = Some(base);
+        self
+    }
+
+    /// the number of bytes in the binary encoding of this cid
+    pub fn encoded_len(&self) -> usize {
+        match self.version {
+            Version::V0 => self.hash.encoded_len(),
+            Version::V1 => {
+                let mut buf = unsigned_varint::encode::u64_buffer();
+                1 + unsigned_varint::encode::u64(self.codec.code(), &mut buf).len()
+                    + self.hash.encoded_len()
+            }
+        }
+    }
+}
+
+impl CodecInfo for Cid {
+    /// Return that we are a CIDv1 object
+    fn preferred_codec() -> Codec {
+        Codec::Cidv1
+    }
+
+    /// Return the content codec of the cid
+    fn codec(&self) -> Codec {
+        self.codec
+    }
+}
+
+impl EncodingInfo for Cid {
+    fn preferred_encoding() -> Base {
+        Base::Base32Lower
+    }
+
+    fn encoding(&self) -> Base {
+        match self.version {
+            Version::V0 => Base::Base58Btc,
+            Version::V1 => self.encoding.unwrap_or_else(Self::preferred_encoding),
+        }
+    }
+}
+
+impl PartialEq for Cid {
+    fn eq(&self, other: &Self) -> bool {
+        self.version == other.version && self.codec == other.codec && self.hash == other.hash
+    }
+}
+
+impl Eq for Cid {}
+
+impl From<&Cid> for Vec<u8> {
+    fn from(cid: &Cid) -> Self {
+        let mut v = Vec::with_capacity(cid.encoded_len());
+        if cid.version == Version::V1 {
+            // add in the version and the content codec
+            let mut buf = unsigned_varint::encode::u64_buffer();
+            v.extend_from_slice(unsigned_varint::encode::u64(cid.version.into(), &mut buf));
+            v.extend_from_slice(unsigned_varint::encode::u64(cid.codec.code(), &mut buf));
+        }
+        // add in the multihash
+        cid.hash
+            .write_to(&mut v)
+            .expect("writing to a Vec never fails");
+        v
+    }
+}
+
+impl From<Cid> for Vec<u8> {
+    fn from(cid: Cid) -> Self {
+        (&cid).into()
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Cid {
+    type Error = Error;
+
+    fn try_from(s: &'a [u8]) -> Result<Self, Self::Error> {
+        let (cid, ptr) = Self::try_decode_from(s)?;
+        if !ptr.is_empty() {
+            return Err(Error::TrailingData {
+                offset: s.len() - ptr.len(),
+                len: ptr.len(),
+            });
+        }
+        Ok(cid)
+    }
+}
+
+impl<'a> TryDecodeFrom<'a> for Cid {
+    type Error = Error;
+
+    fn try_decode_from(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Self::Error> {
+        // a CIDv0 is a bare sha2-256 multihash
+        if bytes.len() >= V0_LEN && bytes[0] == 0x12 && bytes[1] == 0x20 {
+            let (hash, ptr) = Multihash::decode_at(bytes, 0)?;
+            return Ok((Self::new_v0(hash)?, ptr));
+        }
+        // decode the version
+        let (version, ptr) = unsigned_varint::decode::u64(bytes).map_err(|e| match e {
+            unsigned_varint::decode::Error::Insufficient => Error::Truncated { offset: 0 },
+            _ => Error::InvalidVarint { offset: 0 },
+        })?;
+        if version != u64::from(Version::V1) {
+            return Err(Error::InvalidCidVersion(version));
+        }
+        // decode the content codec
+        let offset = bytes.len() - ptr.len();
+        let (codec, ptr) = decode_codec(ptr, offset)?;
+        // decode the multihash
+        let (hash, ptr) = Multihash::decode_at(ptr, bytes.len() - ptr.len())?;
+        Ok((Self::new_v1(codec, hash), ptr))
+    }
+}
+
+/// Formats a CIDv0 as base58btc without a multibase prefix and a CIDv1 as a
+/// multibase string in its base encoding, [`Base::Base32Lower`] by default
+impl fmt::Display for Cid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let v: Vec<u8> = self.into();
+        match self.version {
+            Version::V0 => write!(f, "{}", Base::Base58Btc.encode(v)),
+            Version::V1 => write!(f, "{}", multibase::encode(self.encoding(), v)),
+        }
+    }
+}
+
+/// Parses a CIDv0 from a base58btc string or a CIDv1 from a multibase string
+/// in any base encoding, remembering the base encoding of the string
+impl FromStr for Cid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() == V0_STR_LEN && s.starts_with("Qm") {
+            let v = Base::Base58Btc
+                .decode(s)
+                .map_err(|e| Error::InvalidMultibase(e.to_string()))?;
+            return Self::try_from(v.as_slice());
+        }
+        let (base, v) = multibase::decode(s).map_err(|e| Error::InvalidMultibase(e.to_string()))?;
+        let cid = Self::try_from(v.as_slice())?;
+        if cid.version == Version::V0 {
+            // CIDv0 values are only ever bare base58btc strings
+            return Err(Error::InvalidCidV0);
+        }
+        Ok(cid.with_encoding(base))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V0: &str = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+    const V1: &str = "bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku";
+
+    #[test]
+    fn test_v0_v1() {
+        let v0: Cid = V0.parse().unwrap();
+        assert_eq!(v0.version(), Version::V0);
+        assert_eq!(v0.codec(), Codec::DagPb);
+        assert_eq!(v0.hash().codec(), Codec::Sha2256);
+        assert_eq!(v0.to_string(), V0);
+
+        let v1 = v0.clone().into_v1();
+        assert_eq!(v1.version(), Version::V1);
+        assert_eq!(v1.to_string(), V1);
+        assert_eq!(v1, V1.parse().unwrap());
+        assert_eq!(v1.into_v0().unwrap(), v0);
+    }
+
+    #[test]
+    fn test_v1_roundtrip() {
+        for codec in [Codec::Raw, Codec::DagPb, Codec::DagCbor] {
+            let mh = Multihash::of_bytes(Codec::Blake3, b"for great justice, move every zig!")
+                .unwrap();
+            let cid1 = Cid::new_v1(codec, mh.clone()).with_encoding(Base::Base58Btc);
+            let s = cid1.to_string();
+            assert!(s.starts_with('z'));
+            let cid2: Cid = s.parse().unwrap();
+            assert_eq!(cid1, cid2);
+            assert_eq!(cid2.encoding(), Base::Base58Btc);
+            assert_eq!(cid2.codec(), codec);
+            assert_eq!(cid2.into_hash(), mh);
+
+            let v: Vec<u8> = cid1.clone().into();
+            assert_eq!(v.len(), cid1.encoded_len());
+            assert_eq!(cid1, Cid::try_from(v.as_slice()).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_v0_errors() {
+        let mh = Multihash::of_bytes(Codec::Sha2512, b"move every zig!").unwrap();
+        assert_eq!(Cid::new_v0(mh.clone()), Err(Error::InvalidCidV0));
+        assert_eq!(
+            Cid::new_v1(Codec::Raw, mh).into_v0(),
+            Err(Error::InvalidCidV0)
+        );
+        let mh = Multihash::of_bytes(Codec::Sha2256, b"move every zig!").unwrap();
+        assert_eq!(
+            Cid::new_v1(Codec::Raw, mh).into_v0(),
+            Err(Error::InvalidCidV0)
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(Cid::try_from(&[][..]), Err(Error::Truncated { offset: 0 }));
+        assert_eq!(
+            Cid::try_from(&[0x02, 0x55][..]),
+            Err(Error::InvalidCidVersion(2))
+        );
+        let cid: Cid = V1.parse().unwrap();
+        let v: Vec<u8> = cid.into();
+        assert!(matches!(
+            Cid::try_from(&v[..v.len() - 1]),
+            Err(Error::TruncatedDigest { offset: 4, .. })
+        ));
+    }
+}
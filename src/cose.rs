@@ -0,0 +1,313 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! COSE hash algorithm identifiers (RFC 9054) and CBOR encoding and decoding
+//! of the `COSE_Hash` structure, along with the hash algorithms used by the
+//! JOSE signature algorithms:
+//!
+//! ```text
+//! COSE_Hash = [
+//!     hashAlg : int / tstr,
+//!     hashValue : bstr,
+//!     ? location : tstr
+//! ]
+//! ```
+use crate::{Error, Multihash};
+use multicodec::Codec;
+
+/// CBOR major type for unsigned integers
+const MAJOR_UINT: u8 = 0;
+/// CBOR major type for negative integers
+const MAJOR_NINT: u8 = 1;
+/// CBOR major type for byte strings
+const MAJOR_BSTR: u8 = 2;
+/// CBOR major type for text strings
+const MAJOR_TSTR: u8 = 3;
+/// CBOR major type for arrays
+const MAJOR_ARRAY: u8 = 4;
+
+/// the COSE hash algorithms as the algorithm id, the hash codec and the
+/// length of the digest. SHA-256/64 is a sha2-256 digest truncated to 64 bits.
+/// SHAKE128 (-18) and SHAKE256 (-45) are left out because the SHAKE hashes
+/// aren't among the supported hash codecs
+pub const COSE_ALGORITHMS: [(i64, Codec, usize); 6] = [
+    (-14, Codec::Sha1, 20),
+    (-15, Codec::Sha2256, 8),
+    (-16, Codec::Sha2256, 32),
+    (-17, Codec::Sha2512256, 32),
+    (-43, Codec::Sha2384, 48),
+    (-44, Codec::Sha2512, 64),
+];
+
+/// the JOSE signature algorithms and the hash codec each of them uses
+pub const JOSE_ALGORITHMS: [(&str, Codec); 13] = [
+    ("HS256", Codec::Sha2256),
+    ("HS384", Codec::Sha2384),
+    ("HS512", Codec::Sha2512),
+    ("RS256", Codec::Sha2256),
+    ("RS384", Codec::Sha2384),
+    ("RS512", Codec::Sha2512),
+    ("ES256", Codec::Sha2256),
+    ("ES256K", Codec::Sha2256),
+    ("ES384", Codec::Sha2384),
+    ("ES512", Codec::Sha2512),
+    ("PS256", Codec::Sha2256),
+    ("PS384", Codec::Sha2384),
+    ("PS512", Codec::Sha2512),
+];
+
+/// the COSE algorithm id for the codec and digest length
+pub fn algorithm_id(codec: Codec, len: usize) -> Option<i64> {
+    COSE_ALGORITHMS
+        .iter()
+        .find(|(_, c, l)| *c == codec && *l == len)
+        .map(|(id, _, _)| *id)
+}
+
+/// the codec and digest length for the COSE algorithm id
+pub fn algorithm_codec(id: i64) -> Option<(Codec, usize)> {
+    COSE_ALGORITHMS
+        .iter()
+        .find(|(i, _, _)| *i == id)
+        .map(|(_, c, l)| (*c, *l))
+}
+
+/// the hash codec used by the JOSE signature algorithm
+pub fn jose_algorithm_codec(alg: &str) -> Option<Codec> {
+    JOSE_ALGORITHMS
+        .iter()
+        .find(|(a, _)| *a == alg)
+        .map(|(_, c)| *c)
+}
+
+/// append a CBOR major type and argument in the shortest form
+fn write_head(v: &mut Vec<u8>, major: u8, arg: u64) {
+    let major = major << 5;
+    match arg {
+        0..=23 => v.push(major | arg as u8),
+        24..=0xff => v.extend_from_slice(&[major | 24, arg as u8]),
+        0x100..=0xffff => {
+            v.push(major | 25);
+            v.extend_from_slice(&(arg as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            v.push(major | 26);
+            v.extend_from_slice(&(arg as u32).to_be_bytes());
+        }
+        _ => {
+            v.push(major | 27);
+            v.extend_from_slice(&arg.to_be_bytes());
+        }
+    }
+}
+
+/// read a CBOR major type and argument, returning them with the rest of the
+/// input. indefinite lengths aren't allowed
+fn read_head(bytes: &[u8]) -> Result<(u8, u64, &[u8]), Error> {
+    let truncated = || Error::InvalidCoseHash("truncated".to_string());
+    let (b, rest) = bytes.split_first().ok_or_else(truncated)?;
+    let (major, info) = (b >> 5, b & 0x1f);
+    let n = match info {
+        0..=23 => return Ok((major, u64::from(info), rest)),
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        _ => return Err(Error::InvalidCoseHash("invalid length".to_string())),
+    };
+    if rest.len() < n {
+        return Err(truncated());
+    }
+    let arg = rest[..n]
+        .iter()
+        .fold(0u64, |arg, b| (arg << 8) | u64::from(*b));
+    Ok((major, arg, &rest[n..]))
+}
+
+/// read a CBOR string of the major type, returning its contents
+fn read_string(bytes: &[u8], major: u8) -> Result<(&[u8], &[u8]), Error> {
+    let (m, len, rest) = read_head(bytes)?;
+    if m != major {
+        return Err(Error::InvalidCoseHash(format!(
+            "expected major type {}, found {}",
+            major, m
+        )));
+    }
+    let len = usize::try_from(len)
+        .ok()
+        .filter(|l| *l <= rest.len())
+        .ok_or_else(|| Error::InvalidCoseHash("truncated".to_string()))?;
+    Ok(rest.split_at(len))
+}
+
+impl Multihash {
+    /// the COSE algorithm id of this multihash, truncated sha2-256 digests map
+    /// to SHA-256/64. a digest of a COSE hash codec that isn't one of the
+    /// registered lengths for it is an invalid digest length, expecting the
+    /// longest registered length
+    pub fn cose_algorithm(&self) -> Result<i64, Error> {
+        if let Some(id) = algorithm_id(self.codec, self.hash.len()) {
+            return Ok(id);
+        }
+        let expected = COSE_ALGORITHMS
+            .iter()
+            .filter(|(_, c, _)| *c == self.codec)
+            .map(|(_, _, l)| *l)
+            .max()
+            .ok_or(Error::UnsupportedHash(self.codec))?;
+        Err(Error::InvalidDigestLength {
+            expected,
+            len: self.hash.len(),
+        })
+    }
+
+    /// the CBOR encoded `COSE_Hash` of this multihash
+    pub fn to_cose_hash(&self) -> Result<Vec<u8>, Error> {
+        let id = self.cose_algorithm()?;
+        let mut v = Vec::with_capacity(self.hash.len() + 4);
+        write_head(&mut v, MAJOR_ARRAY, 2);
+        if id < 0 {
+            write_head(&mut v, MAJOR_NINT, (-1 - id) as u64);
+        } else {
+            write_head(&mut v, MAJOR_UINT, id as u64);
+        }
+        write_head(&mut v, MAJOR_BSTR, self.hash.len() as u64);
+        v.extend_from_slice(&self.hash);
+        Ok(v)
+    }
+
+    /// decode a CBOR encoded `COSE_Hash`, the optional location is ignored
+    pub fn from_cose_hash(bytes: &[u8]) -> Result<Self, Error> {
+        let (major, len, ptr) = read_head(bytes)?;
+        if major != MAJOR_ARRAY || !(2..=3).contains(&len) {
+            return Err(Error::InvalidCoseHash(
+                "expected an array of 2 or 3".to_string(),
+            ));
+        }
+        // decode the algorithm id, text algorithm names aren't registered
+        let (major, arg, ptr) = read_head(ptr)?;
+        let id = match major {
+            MAJOR_UINT => i64::try_from(arg).ok(),
+            MAJOR_NINT => i64::try_from(arg).ok().map(|a| -1 - a),
+            _ => None,
+        }
+        .ok_or_else(|| Error::InvalidCoseHash("unsupported hash algorithm".to_string()))?;
+        let (codec, expected) = algorithm_codec(id)
+            .ok_or_else(|| Error::InvalidCoseHash(format!("unknown hash algorithm {}", id)))?;
+        // decode the hash value
+        let (hash, mut ptr) = read_string(ptr, MAJOR_BSTR)?;
+        if hash.len() != expected {
+            return Err(Error::InvalidDigestLength {
+                expected,
+                len: hash.len(),
+            });
+        }
+        // skip the location
+        if len == 3 {
+            let (_, p) = read_string(ptr, MAJOR_TSTR)?;
+            ptr = p;
+        }
+        if !ptr.is_empty() {
+            return Err(Error::TrailingData {
+                offset: bytes.len() - ptr.len(),
+                len: ptr.len(),
+            });
+        }
+        Ok(Self {
+            codec,
+            hash: hash.to_vec(),
+            encoding: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = b"for great justice, move every zig!";
+
+    #[test]
+    fn test_cose_hash_roundtrip() {
+        for (id, codec, len) in COSE_ALGORITHMS {
+            let mut mh = Multihash::of_bytes(codec, DATA).unwrap();
+            mh.hash.truncate(len);
+            assert_eq!(mh.cose_algorithm().unwrap(), id);
+            let cbor = mh.to_cose_hash().unwrap();
+            assert_eq!(Multihash::from_cose_hash(&cbor).unwrap(), mh);
+        }
+    }
+
+    #[test]
+    fn test_cose_hash_encoding() {
+        let mh = Multihash::of_bytes(Codec::Sha2256, DATA).unwrap();
+        let cbor = mh.to_cose_hash().unwrap();
+        // [-16, h'...'] with a one byte length for the digest
+        assert_eq!(cbor[..4], [0x82, 0x2f, 0x58, 0x20]);
+        assert_eq!(cbor[4..], *mh.as_ref());
+
+        let mh = Multihash::of_bytes(Codec::Sha2384, DATA).unwrap();
+        let cbor = mh.to_cose_hash().unwrap();
+        // -43 takes a one byte argument
+        assert_eq!(cbor[..5], [0x82, 0x38, 0x2a, 0x58, 0x30]);
+
+        // the same structure from serde_cbor, with a location
+        let value = serde_cbor::Value::Array(vec![
+            serde_cbor::Value::Integer(-43),
+            serde_cbor::Value::Bytes(mh.as_ref().to_vec()),
+            serde_cbor::Value::Text("https://example.com".to_string()),
+        ]);
+        let cbor = serde_cbor::to_vec(&value).unwrap();
+        assert_eq!(Multihash::from_cose_hash(&cbor).unwrap(), mh);
+    }
+
+    #[test]
+    fn test_cose_hash_errors() {
+        let mh = Multihash::of_bytes(Codec::Blake3, DATA).unwrap();
+        assert_eq!(
+            mh.to_cose_hash(),
+            Err(Error::UnsupportedHash(Codec::Blake3))
+        );
+        // a sha2-256 digest truncated to a length without an algorithm id
+        let mut mh = Multihash::of_bytes(Codec::Sha2256, DATA).unwrap();
+        mh.hash.truncate(16);
+        let err = Error::InvalidDigestLength {
+            expected: 32,
+            len: 16,
+        };
+        assert_eq!(mh.cose_algorithm(), Err(err.clone()));
+        assert_eq!(mh.to_cose_hash(), Err(err));
+        // an unknown algorithm
+        assert!(matches!(
+            Multihash::from_cose_hash(&[0x82, 0x20, 0x40]),
+            Err(Error::InvalidCoseHash(_))
+        ));
+        // a text algorithm name
+        assert!(matches!(
+            Multihash::from_cose_hash(&[0x82, 0x61, 0x61, 0x40]),
+            Err(Error::InvalidCoseHash(_))
+        ));
+        // the wrong length of digest
+        assert_eq!(
+            Multihash::from_cose_hash(&[0x82, 0x2f, 0x41, 0x00]),
+            Err(Error::InvalidDigestLength {
+                expected: 32,
+                len: 1
+            })
+        );
+        // truncated
+        let cbor = Multihash::of_bytes(Codec::Sha1, DATA)
+            .unwrap()
+            .to_cose_hash()
+            .unwrap();
+        assert!(matches!(
+            Multihash::from_cose_hash(&cbor[..cbor.len() - 1]),
+            Err(Error::InvalidCoseHash(_))
+        ));
+    }
+
+    #[test]
+    fn test_jose() {
+        assert_eq!(jose_algorithm_codec("ES384"), Some(Codec::Sha2384));
+        assert_eq!(jose_algorithm_codec("PS512"), Some(Codec::Sha2512));
+        assert_eq!(jose_algorithm_codec("EdDSA"), None);
+    }
+}
@@ -0,0 +1,246 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! Conversion between [`MultihashSet`] and the digest maps used in supply
+//! chain metadata: in-toto/SLSA `DigestSet` maps such as
+//! `{"sha256": "<hex>"}`, SPDX `checksums` and CycloneDX `hashes`. Each format
+//! has its own table of algorithm names.
+use crate::{Error, Multihash, MultihashSet};
+use multicodec::Codec;
+use multiutil::CodecInfo;
+use std::collections::BTreeMap;
+
+/// the in-toto `DigestSet` algorithm names
+pub const IN_TOTO_ALGORITHMS: [(Codec, &str); 15] = [
+    (Codec::Sha2224, "sha224"),
+    (Codec::Sha2256, "sha256"),
+    (Codec::Sha2384, "sha384"),
+    (Codec::Sha2512, "sha512"),
+    (Codec::Sha2512224, "sha512_224"),
+    (Codec::Sha2512256, "sha512_256"),
+    (Codec::Sha3224, "sha3_224"),
+    (Codec::Sha3256, "sha3_256"),
+    (Codec::Sha3384, "sha3_384"),
+    (Codec::Sha3512, "sha3_512"),
+    (Codec::Blake2B512, "blake2b"),
+    (Codec::Blake2S256, "blake2s"),
+    (Codec::Ripemd160, "ripemd160"),
+    (Codec::Sha1, "sha1"),
+    (Codec::Md5, "md5"),
+];
+
+/// the SPDX checksum algorithm names
+pub const SPDX_ALGORITHMS: [(Codec, &str); 13] = [
+    (Codec::Md5, "MD5"),
+    (Codec::Sha1, "SHA1"),
+    (Codec::Sha2224, "SHA224"),
+    (Codec::Sha2256, "SHA256"),
+    (Codec::Sha2384, "SHA384"),
+    (Codec::Sha2512, "SHA512"),
+    (Codec::Sha3256, "SHA3-256"),
+    (Codec::Sha3384, "SHA3-384"),
+    (Codec::Sha3512, "SHA3-512"),
+    (Codec::Blake2B256, "BLAKE2b-256"),
+    (Codec::Blake2B384, "BLAKE2b-384"),
+    (Codec::Blake2B512, "BLAKE2b-512"),
+    (Codec::Blake3, "BLAKE3"),
+];
+
+/// the CycloneDX hash algorithm names
+pub const CYCLONEDX_ALGORITHMS: [(Codec, &str); 12] = [
+    (Codec::Md5, "MD5"),
+    (Codec::Sha1, "SHA-1"),
+    (Codec::Sha2256, "SHA-256"),
+    (Codec::Sha2384, "SHA-384"),
+    (Codec::Sha2512, "SHA-512"),
+    (Codec::Sha3256, "SHA3-256"),
+    (Codec::Sha3384, "SHA3-384"),
+    (Codec::Sha3512, "SHA3-512"),
+    (Codec::Blake2B256, "BLAKE2b-256"),
+    (Codec::Blake2B384, "BLAKE2b-384"),
+    (Codec::Blake2B512, "BLAKE2b-512"),
+    (Codec::Blake3, "BLAKE3"),
+];
+
+/// The format of a digest map, which decides the algorithm names
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DigestFormat {
+    /// in-toto and SLSA `DigestSet`
+    InToto,
+    /// SPDX `checksums`
+    Spdx,
+    /// CycloneDX `hashes`
+    CycloneDx,
+}
+
+impl DigestFormat {
+    /// the table of algorithm names for this format
+    fn algorithms(&self) -> &'static [(Codec, &'static str)] {
+        match self {
+            DigestFormat::InToto => &IN_TOTO_ALGORITHMS,
+            DigestFormat::Spdx => &SPDX_ALGORITHMS,
+            DigestFormat::CycloneDx => &CYCLONEDX_ALGORITHMS,
+        }
+    }
+
+    /// the algorithm name of the codec in this format
+    pub fn algorithm_name(&self, codec: Codec) -> Option<&'static str> {
+        self.algorithms()
+            .iter()
+            .find(|(c, _)| *c == codec)
+            .map(|(_, n)| *n)
+    }
+
+    /// the codec of the algorithm name in this format
+    pub fn algorithm_codec(&self, name: &str) -> Option<Codec> {
+        self.algorithms()
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(c, _)| *c)
+    }
+}
+
+impl MultihashSet {
+    /// the digest map of the set in the format, algorithm name to lowercase
+    /// hex digest. none of the formats can say a digest is truncated so a
+    /// truncated digest in the set is an error
+    pub fn to_digest_set(&self, format: DigestFormat) -> Result<BTreeMap<String, String>, Error> {
+        self.iter()
+            .map(|mh| {
+                let name = format
+                    .algorithm_name(mh.codec())
+                    .ok_or(Error::UnsupportedHash(mh.codec()))?;
+                mh.check_full_digest()?;
+                Ok((name.to_string(), hex::encode(mh.as_ref())))
+            })
+            .collect()
+    }
+
+    /// build a set from the algorithm name and hex digest pairs of a digest
+    /// map in the format. algorithms with names not in the format table are
+    /// skipped since the formats allow other kinds of digests
+    pub fn from_digest_set<K, V>(
+        format: DigestFormat,
+        digests: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Self, Error>
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut set = Self::new();
+        for (name, digest) in digests {
+            let Some(codec) = format.algorithm_codec(name.as_ref()) else {
+                continue;
+            };
+            let digest =
+                hex::decode(digest.as_ref()).map_err(|e| Error::InvalidDigestSet(e.to_string()))?;
+            if set.insert(Multihash::from_digest(codec, digest)?).is_some() {
+                return Err(Error::DuplicateHash(codec));
+            }
+        }
+        Ok(set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = b"for great justice, move every zig!";
+
+    #[test]
+    fn test_formats_roundtrip() {
+        for format in [
+            DigestFormat::InToto,
+            DigestFormat::Spdx,
+            DigestFormat::CycloneDx,
+        ] {
+            let set =
+                MultihashSet::of_bytes([Codec::Sha2256, Codec::Sha2512, Codec::Md5], DATA).unwrap();
+            let digests = set.to_digest_set(format).unwrap();
+            assert_eq!(digests.len(), 3);
+            let set2 = MultihashSet::from_digest_set(format, &digests).unwrap();
+            for mh in &set {
+                assert_eq!(set2.get(mh.codec()), Some(mh));
+            }
+        }
+    }
+
+    #[test]
+    fn test_in_toto() {
+        let set = MultihashSet::of_bytes([Codec::Sha2256, Codec::Blake2B512], DATA).unwrap();
+        let digests = set.to_digest_set(DigestFormat::InToto).unwrap();
+        let names: Vec<&str> = digests.keys().map(|k| k.as_str()).collect();
+        assert_eq!(names, vec!["blake2b", "sha256"]);
+
+        // other kinds of digests, like git commits, are skipped
+        let set2 = MultihashSet::from_digest_set(
+            DigestFormat::InToto,
+            [
+                ("gitCommit", "abcd"),
+                ("sha256", digests["sha256"].as_str()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(set2.codecs(), vec![Codec::Sha2256]);
+    }
+
+    #[test]
+    fn test_truncated() {
+        // a truncated sha-512 next to a full sha-256 in every format
+        let mut set = MultihashSet::of_bytes([Codec::Sha2256, Codec::Sha2512], DATA).unwrap();
+        let mh = set.remove(Codec::Sha2512).unwrap();
+        set.insert(Multihash {
+            codec: mh.codec(),
+            hash: mh.as_ref()[..32].to_vec(),
+            encoding: None,
+        });
+        for format in [
+            DigestFormat::InToto,
+            DigestFormat::Spdx,
+            DigestFormat::CycloneDx,
+        ] {
+            assert_eq!(
+                set.to_digest_set(format),
+                Err(Error::InvalidDigestLength {
+                    expected: 64,
+                    len: 32
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(
+            DigestFormat::InToto.algorithm_name(Codec::Sha2512256),
+            Some("sha512_256")
+        );
+        assert_eq!(
+            DigestFormat::Spdx.algorithm_name(Codec::Sha3256),
+            Some("SHA3-256")
+        );
+        assert_eq!(
+            DigestFormat::CycloneDx.algorithm_name(Codec::Sha1),
+            Some("SHA-1")
+        );
+        assert_eq!(DigestFormat::CycloneDx.algorithm_codec("SHA256"), None);
+        assert_eq!(DigestFormat::Spdx.algorithm_codec("MD5"), Some(Codec::Md5));
+    }
+
+    #[test]
+    fn test_errors() {
+        let set = MultihashSet::of_bytes([Codec::Ripemd320], DATA).unwrap();
+        assert_eq!(
+            set.to_digest_set(DigestFormat::Spdx),
+            Err(Error::UnsupportedHash(Codec::Ripemd320))
+        );
+        assert!(matches!(
+            MultihashSet::from_digest_set(DigestFormat::Spdx, [("SHA1", "xyz")]),
+            Err(Error::InvalidDigestSet(_))
+        ));
+        let sha1 = hex::encode(Multihash::of_bytes(Codec::Sha1, DATA).unwrap());
+        assert_eq!(
+            MultihashSet::from_digest_set(DigestFormat::Spdx, [("SHA1", &sha1), ("SHA1", &sha1)]),
+            Err(Error::DuplicateHash(Codec::Sha1))
+        );
+    }
+}
@@ -9,10 +9,251 @@ pub enum Error {
     /// Multiutil error
     #[error(transparent)]
     Multiutil(#[from] multiutil::Error),
+    /// The buffer is too small to encode into
+    #[error("Buffer too small: {needed} bytes needed, {available} available")]
+    BufferTooSmall {
+        /// the number of bytes needed
+        needed: usize,
+        /// the number of bytes available
+        available: usize,
+    },
+    /// The input ended in the middle of a multihash
+    #[error("Truncated multihash at byte offset {offset}")]
+    Truncated {
+        /// the byte offset of the start of the incomplete value
+        offset: usize,
+    },
+    /// The input ended before the end of the digest
+    #[error("Truncated digest at byte offset {offset}: {expected} bytes expected, {available} available")]
+    TruncatedDigest {
+        /// the byte offset of the start of the digest
+        offset: usize,
+        /// the length of the digest
+        expected: usize,
+        /// the number of digest bytes in the input
+        available: usize,
+    },
+    /// The digest length varint is invalid or too large
+    #[error("Digest length overflow at byte offset {offset}")]
+    LengthOverflow {
+        /// the byte offset of the digest length
+        offset: usize,
+    },
+    /// A varint is invalid, either too large or not minimally encoded
+    #[error("Invalid varint at byte offset {offset}")]
+    InvalidVarint {
+        /// the byte offset of the varint
+        offset: usize,
+    },
+    /// There is data left over after decoding
+    #[error("Trailing data at byte offset {offset}: {len} bytes")]
+    TrailingData {
+        /// the byte offset of the trailing data
+        offset: usize,
+        /// the number of trailing bytes
+        len: usize,
+    },
+    /// The codec value isn't a known codec
+    #[error("Unknown codec 0x{code:x} at byte offset {offset}")]
+    UnknownCodec {
+        /// the byte offset of the codec
+        offset: usize,
+        /// the codec value
+        code: u64,
+    },
+    /// The multibase string is invalid
+    #[error("Invalid multibase: {0}")]
+    InvalidMultibase(String),
+    /// The digest is too large for a fixed size multihash type
+    #[error("Digest too large: {len} bytes, {max} maximum")]
+    DigestTooLarge {
+        /// the length of the digest
+        len: usize,
+        /// the maximum digest length
+        max: usize,
+    },
+    /// An error from the multiformats multihash or cid crates
+    #[error("Multiformats error: {0}")]
+    Multiformats(String),
+    /// The digest isn't the right length for the hash codec
+    #[error("Invalid digest length: {len} bytes, {expected} expected")]
+    InvalidDigestLength {
+        /// the length the hash codec produces
+        expected: usize,
+        /// the length of the digest
+        len: usize,
+    },
+    /// The Subresource Integrity string is invalid
+    #[error("Invalid SRI: {0}")]
+    InvalidSri(String),
+    /// The HTTP digest field value is invalid
+    #[error("Invalid digest field: {0}")]
+    InvalidDigestField(String),
+    /// The OCI digest is invalid
+    #[error("Invalid OCI digest: {0}")]
+    InvalidOciDigest(String),
+    /// The named information URI is invalid
+    #[error("Invalid ni URI: {0}")]
+    InvalidNi(String),
+    /// The fingerprint is invalid
+    #[error("Invalid fingerprint: {0}")]
+    InvalidFingerprint(String),
+    /// The digest map is invalid
+    #[error("Invalid digest set: {0}")]
+    InvalidDigestSet(String),
+    /// The DER encoded DigestInfo is invalid
+    #[error("Invalid DigestInfo: {0}")]
+    InvalidDigestInfo(String),
+    /// The CBOR encoded COSE_Hash is invalid
+    #[error("Invalid COSE_Hash: {0}")]
+    InvalidCoseHash(String),
+    /// The cid version isn't a supported version
+    #[error("Invalid CID version: {0}")]
+    InvalidCidVersion(u64),
+    /// A CIDv0 must be a dag-pb sha2-256 multihash encoded as base58btc
+    #[error("Invalid CIDv0, must be a dag-pb sha2-256 multihash")]
+    InvalidCidV0,
+    /// The key encoding of a multihash is invalid
+    #[error("Invalid multihash key")]
+    InvalidKey,
     /// Missing hash data
     #[error("Missing hash data")]
     MissingHash,
+    /// I/O error while reading the data to hash
+    #[error("I/O error ({kind:?}): {msg}")]
+    Io {
+        /// the kind of I/O error
+        kind: std::io::ErrorKind,
+        /// the I/O error message
+        msg: String,
+    },
     /// Error with the hash scheme
     #[error("Unsupported hash algorithm: {0}")]
     UnsupportedHash(multicodec::Codec),
+    /// The data doesn't match the hash
+    #[error("Hash mismatch: {0}")]
+    HashMismatch(multicodec::Codec),
+    /// None of the trusted hash algorithms are in the set
+    #[error("No trusted hash")]
+    NoTrustedHash,
+    /// More than one hash with the same algorithm in a set
+    #[error("Duplicate hash algorithm: {0}")]
+    DuplicateHash(multicodec::Codec),
 }
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io {
+            kind: e.kind(),
+            msg: e.to_string(),
+        }
+    }
+}
+
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        use Error::*;
+        match (self, other) {
+            // the wrapped errors aren't comparable so compare their messages
+            (Multicodec(a), Multicodec(b)) => a.to_string() == b.to_string(),
+            (Multiutil(a), Multiutil(b)) => a.to_string() == b.to_string(),
+            (
+                BufferTooSmall {
+                    needed: n1,
+                    available: a1,
+                },
+                BufferTooSmall {
+                    needed: n2,
+                    available: a2,
+                },
+            ) => n1 == n2 && a1 == a2,
+            (Truncated { offset: o1 }, Truncated { offset: o2 }) => o1 == o2,
+            (
+                TruncatedDigest {
+                    offset: o1,
+                    expected: e1,
+                    available: a1,
+                },
+                TruncatedDigest {
+                    offset: o2,
+                    expected: e2,
+                    available: a2,
+                },
+            ) => o1 == o2 && e1 == e2 && a1 == a2,
+            (LengthOverflow { offset: o1 }, LengthOverflow { offset: o2 }) => o1 == o2,
+            (InvalidVarint { offset: o1 }, InvalidVarint { offset: o2 }) => o1 == o2,
+            (TrailingData { offset: o1, len: l1 }, TrailingData { offset: o2, len: l2 }) => {
+                o1 == o2 && l1 == l2
+            }
+            (UnknownCodec { offset: o1, code: c1 }, UnknownCodec { offset: o2, code: c2 }) => {
+                o1 == o2 && c1 == c2
+            }
+            (InvalidMultibase(a), InvalidMultibase(b)) => a == b,
+            (DigestTooLarge { len: l1, max: m1 }, DigestTooLarge { len: l2, max: m2 }) => {
+                l1 == l2 && m1 == m2
+            }
+            (Multiformats(a), Multiformats(b)) => a == b,
+            (
+                InvalidDigestLength {
+                    expected: e1,
+                    len: l1,
+                },
+                InvalidDigestLength {
+                    expected: e2,
+                    len: l2,
+                },
+            ) => e1 == e2 && l1 == l2,
+            (InvalidSri(a), InvalidSri(b)) => a == b,
+            (InvalidDigestField(a), InvalidDigestField(b)) => a == b,
+            (InvalidOciDigest(a), InvalidOciDigest(b)) => a == b,
+            (InvalidNi(a), InvalidNi(b)) => a == b,
+            (InvalidFingerprint(a), InvalidFingerprint(b)) => a == b,
+            (InvalidDigestSet(a), InvalidDigestSet(b)) => a == b,
+            (InvalidDigestInfo(a), InvalidDigestInfo(b)) => a == b,
+            (InvalidCoseHash(a), InvalidCoseHash(b)) => a == b,
+            (InvalidCidVersion(a), InvalidCidVersion(b)) => a == b,
+            (InvalidCidV0, InvalidCidV0) => true,
+            (InvalidKey, InvalidKey) => true,
+            (MissingHash, MissingHash) => true,
+            (Io { kind: k1, msg: m1 }, Io { kind: k2, msg: m2 }) => k1 == k2 && m1 == m2,
+            (UnsupportedHash(a), UnsupportedHash(b)) => a == b,
+            (HashMismatch(a), HashMismatch(b)) => a == b,
+            (NoTrustedHash, NoTrustedHash) => true,
+            (DuplicateHash(a), DuplicateHash(b)) => a == b,
+            // listed by variant rather than with a wildcard so that adding a
+            // variant without a comparison above fails to compile
+            (Multicodec(_), _)
+            | (Multiutil(_), _)
+            | (BufferTooSmall { .. }, _)
+            | (Truncated { .. }, _)
+            | (TruncatedDigest { .. }, _)
+            | (LengthOverflow { .. }, _)
+            | (InvalidVarint { .. }, _)
+            | (TrailingData { .. }, _)
+            | (UnknownCodec { .. }, _)
+            | (InvalidMultibase(_), _)
+            | (DigestTooLarge { .. }, _)
+            | (Multiformats(_), _)
+            | (InvalidDigestLength { .. }, _)
+            | (InvalidSri(_), _)
+            | (InvalidDigestField(_), _)
+            | (InvalidOciDigest(_), _)
+            | (InvalidNi(_), _)
+            | (InvalidFingerprint(_), _)
+            | (InvalidDigestSet(_), _)
+            | (InvalidDigestInfo(_), _)
+            | (InvalidCoseHash(_), _)
+            | (InvalidCidVersion(_), _)
+            | (InvalidCidV0, _)
+            | (InvalidKey, _)
+            | (MissingHash, _)
+            | (Io { .. }, _)
+            | (UnsupportedHash(_), _)
+            | (HashMismatch(_), _)
+            | (NoTrustedHash, _)
+            | (DuplicateHash(_), _) => false,
+        }
+    }
+}
+
+impl Eq for Error {}
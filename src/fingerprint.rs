@@ -0,0 +1,223 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! Fingerprint notations for [`Multihash`]: OpenSSH `SHA256:<base64>` and
+//! `MD5:aa:bb:..`, X.509 colon separated uppercase hex and grouped hex such
+//! as PGP fingerprints.
+use crate::{Error, Multihash};
+use multibase::Base;
+use multicodec::Codec;
+
+/// the OpenSSH fingerprint hash names
+pub const SSH_ALGORITHMS: [(Codec, &str); 5] = [
+    (Codec::Md5, "MD5"),
+    (Codec::Sha1, "SHA1"),
+    (Codec::Sha2256, "SHA256"),
+    (Codec::Sha2384, "SHA384"),
+    (Codec::Sha2512, "SHA512"),
+];
+
+/// the OpenSSH fingerprint hash name of the codec
+pub fn algorithm_name(codec: Codec) -> Option<&'static str> {
+    SSH_ALGORITHMS
+        .iter()
+        .find(|(c, _)| *c == codec)
+        .map(|(_, n)| *n)
+}
+
+/// the codec of the OpenSSH fingerprint hash name
+pub fn algorithm_codec(name: &str) -> Option<Codec> {
+    SSH_ALGORITHMS
+        .iter()
+        .find(|(_, n)| n.eq_ignore_ascii_case(name))
+        .map(|(c, _)| *c)
+}
+
+/// the bytes as hex pairs joined by colons
+fn colon_hex(bytes: &[u8], upper: bool) -> String {
+    let hex = if upper {
+        hex::encode_upper(bytes)
+    } else {
+        hex::encode(bytes)
+    };
+    let pairs: Vec<&str> = (0..hex.len()).step_by(2).map(|i| &hex[i..i + 2]).collect();
+    pairs.join(":")
+}
+
+/// decode hex pairs joined by colons
+fn from_colon_hex(s: &str) -> Result<Vec<u8>, Error> {
+    s.split(':')
+        .map(|pair| match hex::decode(pair).as_deref() {
+            Ok([b]) => Ok(*b),
+            _ => Err(Error::InvalidFingerprint(format!(
+                "invalid hex pair '{}'",
+                pair
+            ))),
+        })
+        .collect()
+}
+
+impl Multihash {
+    /// the OpenSSH fingerprint of this multihash, `MD5:aa:bb:..` for md5 and
+    /// `SHA256:<base64>` without padding for the sha hashes. the fingerprint
+    /// has no way to say a digest is truncated so a truncated digest is an
+    /// error
+    pub fn to_ssh_fingerprint(&self) -> Result<String, Error> {
+        let name = algorithm_name(self.codec).ok_or(Error::UnsupportedHash(self.codec))?;
+        self.check_full_digest()?;
+        if self.codec == Codec::Md5 {
+            Ok(format!("{}:{}", name, colon_hex(&self.hash, false)))
+        } else {
+            Ok(format!("{}:{}", name, Base::Base64.encode(&self.hash)))
+        }
+    }
+
+    /// parse an OpenSSH fingerprint, a bare colon separated hex fingerprint
+    /// from older versions of OpenSSH is taken to be md5
+    pub fn from_ssh_fingerprint(s: &str) -> Result<Self, Error> {
+        let (name, fp) = s
+            .split_once(':')
+            .filter(|(name, _)| name.len() > 2)
+            .unwrap_or(("MD5", s));
+        let codec = algorithm_codec(name).ok_or_else(|| {
+            Error::InvalidFingerprint(format!("unsupported hash name '{}'", name))
+        })?;
+        let digest = if codec == Codec::Md5 {
+            from_colon_hex(fp)?
+        } else {
+            Base::Base64
+                .decode(fp.trim_end_matches('='))
+                .map_err(|e| Error::InvalidFingerprint(e.to_string()))?
+        };
+        Self::from_digest(codec, digest)
+    }
+
+    /// the X.509 style fingerprint of this multihash, colon separated
+    /// uppercase hex, e.g. `AB:CD:..`
+    pub fn to_x509_fingerprint(&self) -> String {
+        colon_hex(&self.hash, true)
+    }
+
+    /// parse an X.509 style fingerprint made with the hash codec, the hex may
+    /// be upper or lowercase
+    pub fn from_x509_fingerprint(codec: Codec, s: &str) -> Result<Self, Error> {
+        Self::from_digest(codec, from_colon_hex(s.trim())?)
+    }
+
+    /// the digest of this multihash as uppercase hex in groups of the given
+    /// number of characters separated by spaces, e.g. a PGP fingerprint with
+    /// a group length of 4
+    pub fn to_grouped_hex(&self, group_len: usize) -> String {
+        let hex = hex::encode_upper(&self.hash);
+        let groups: Vec<&str> = (0..hex.len())
+            .step_by(group_len.max(1))
+            .map(|i| &hex[i..hex.len().min(i + group_len.max(1))])
+            .collect();
+        groups.join(" ")
+    }
+
+    /// parse a grouped hex fingerprint made with the hash codec, all
+    /// whitespace between the groups is ignored
+    pub fn from_grouped_hex(codec: Codec, s: &str) -> Result<Self, Error> {
+        let hex: String = s.split_whitespace().collect();
+        let digest = hex::decode(hex).map_err(|e| Error::InvalidFingerprint(e.to_string()))?;
+        Self::from_digest(codec, digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA256: &str = "SHA256:47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU";
+    const MD5: &str = "MD5:d4:1d:8c:d9:8f:00:b2:04:e9:80:09:98:ec:f8:42:7e";
+
+    #[test]
+    fn test_ssh_fingerprint() {
+        let mh = Multihash::of_bytes(Codec::Sha2256, b"").unwrap();
+        assert_eq!(mh.to_ssh_fingerprint().unwrap(), SHA256);
+        assert_eq!(Multihash::from_ssh_fingerprint(SHA256).unwrap(), mh);
+
+        let mh = Multihash::of_bytes(Codec::Md5, b"").unwrap();
+        assert_eq!(mh.to_ssh_fingerprint().unwrap(), MD5);
+        assert_eq!(Multihash::from_ssh_fingerprint(MD5).unwrap(), mh);
+        assert_eq!(Multihash::from_ssh_fingerprint(&MD5[4..]).unwrap(), mh);
+
+        for (codec, _) in SSH_ALGORITHMS {
+            let mh = Multihash::of_bytes(codec, b"move every zig!").unwrap();
+            let fp = mh.to_ssh_fingerprint().unwrap();
+            assert_eq!(Multihash::from_ssh_fingerprint(&fp).unwrap(), mh);
+        }
+    }
+
+    #[test]
+    fn test_ssh_fingerprint_errors() {
+        let mh = Multihash::of_bytes(Codec::Blake3, b"").unwrap();
+        assert_eq!(
+            mh.to_ssh_fingerprint(),
+            Err(Error::UnsupportedHash(Codec::Blake3))
+        );
+        assert!(matches!(
+            Multihash::from_ssh_fingerprint("BLAKE3:AAAA"),
+            Err(Error::InvalidFingerprint(_))
+        ));
+        assert!(matches!(
+            Multihash::from_ssh_fingerprint("MD5:d4:1d:8"),
+            Err(Error::InvalidFingerprint(_))
+        ));
+        assert_eq!(
+            Multihash::from_ssh_fingerprint("SHA256:AAAA"),
+            Err(Error::InvalidDigestLength {
+                expected: 32,
+                len: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_ssh_fingerprint_truncated() {
+        // both notations, base64 for sha and colon separated hex for md5
+        for (fp, len) in [(SHA256, 32), (MD5, 16)] {
+            let mh = Multihash::from_ssh_fingerprint(fp).unwrap();
+            let truncated = Multihash {
+                codec: mh.codec,
+                hash: mh.as_ref()[..len / 2].to_vec(),
+                encoding: None,
+            };
+            assert_eq!(
+                truncated.to_ssh_fingerprint(),
+                Err(Error::InvalidDigestLength {
+                    expected: len,
+                    len: len / 2
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_x509_fingerprint() {
+        let mh = Multihash::of_bytes(Codec::Sha1, b"").unwrap();
+        let fp = mh.to_x509_fingerprint();
+        assert_eq!(
+            fp,
+            "DA:39:A3:EE:5E:6B:4B:0D:32:55:BF:EF:95:60:18:90:AF:D8:07:09"
+        );
+        assert_eq!(
+            Multihash::from_x509_fingerprint(Codec::Sha1, &fp).unwrap(),
+            mh
+        );
+        assert_eq!(
+            Multihash::from_x509_fingerprint(Codec::Sha1, &fp.to_lowercase()).unwrap(),
+            mh
+        );
+    }
+
+    #[test]
+    fn test_grouped_hex() {
+        let mh = Multihash::of_bytes(Codec::Sha1, b"").unwrap();
+        let fp = mh.to_grouped_hex(4);
+        assert_eq!(fp, "DA39 A3EE 5E6B 4B0D 3255 BFEF 9560 1890 AFD8 0709");
+        assert_eq!(Multihash::from_grouped_hex(Codec::Sha1, &fp).unwrap(), mh);
+        // pgp puts an extra space in the middle
+        let pgp = "DA39 A3EE 5E6B 4B0D 3255  BFEF 9560 1890 AFD8 0709";
+        assert_eq!(Multihash::from_grouped_hex(Codec::Sha1, pgp).unwrap(), mh);
+    }
+}
@@ -0,0 +1,200 @@
+// SPDX-License-Idnetifier: Apache-2.0
+use crate::{Error, Multihash};
+use digest::Digest;
+use multicodec::Codec;
+use std::{
+    fs::File,
+    io::{ErrorKind, Read},
+    path::Path,
+};
+use typenum::consts::*;
+
+/// the size of the buffer used when hashing from a reader
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// generates the hasher enum, one variant per codec, named after the codec
+macro_rules! hashers {
+    ($($codec:ident => $hasher:ty),+ $(,)?) => {
+        /// Statically dispatched hasher state for all of the supported hash
+        /// codecs. The blake3 state makes this large but it lives on the stack
+        /// so that hashing doesn't require any allocations beyond the digest.
+        #[allow(clippy::large_enum_variant)]
+        #[derive(Clone)]
+        pub(crate) enum Hasher {
+            $(
+                $codec($hasher),
+            )+
+        }
+
+        impl Hasher {
+            /// create a new hasher for the given codec
+            pub(crate) fn new(codec: Codec) -> Result<Self, Error> {
+                match codec {
+                    $(
+                        Codec::$codec => Ok(Hasher::$codec(<$hasher as Digest>::new())),
+                    )+
+                    _ => Err(Error::UnsupportedHash(codec)),
+                }
+            }
+
+            /// the codec of the hash this hasher produces
+            pub(crate) fn codec(&self) -> Codec {
+                match self {
+                    $(
+                        Hasher::$codec(_) => Codec::$codec,
+                    )+
+                }
+            }
+
+            /// the size of the digest in bytes
+            pub(crate) fn output_size(&self) -> usize {
+                match self {
+                    $(
+                        Hasher::$codec(_) => <$hasher as Digest>::output_size(),
+                    )+
+                }
+            }
+
+            /// feed more data into the hasher
+            pub(crate) fn update(&mut self, data: &[u8]) {
+                match self {
+                    $(
+                        Hasher::$codec(h) => Digest::update(h, data),
+                    )+
+                }
+            }
+
+            /// finalize the hash, replacing the contents of the given buffer
+            /// with the digest and reusing its allocation if it is big enough
+            pub(crate) fn finalize_into(self, hash: &mut Vec<u8>) {
+                hash.clear();
+                match self {
+                    $(
+                        Hasher::$codec(h) => hash.extend_from_slice(&Digest::finalize(h)),
+                    )+
+                }
+            }
+        }
+    };
+}
+
+/// the size of the digest in bytes for the given codec, if it is supported
+pub(crate) fn digest_len(codec: Codec) -> Option<usize> {
+    Hasher::new(codec).ok().map(|h| h.output_size())
+}
+
+/// read everything from the reader, passing each chunk read to the closure
+pub(crate) fn read_chunks(
+    mut reader: impl Read,
+    mut f: impl FnMut(&[u8]),
+) -> Result<(), Error> {
+    let mut buf = vec![0u8; READ_BUFFER_SIZE];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => f(&buf[..n]),
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+hashers! {
+    Blake2B224 => blake2::Blake2b<U28>,
+    Blake2B256 => blake2::Blake2b<U32>,
+    Blake2B384 => blake2::Blake2b<U48>,
+    Blake2B512 => blake2::Blake2b<U64>,
+    Blake2S224 => blake2::Blake2s<U28>,
+    Blake2S256 => blake2::Blake2s<U32>,
+    Blake3 => blake3::Hasher,
+    Md5 => md5::Md5,
+    Ripemd128 => ripemd::Ripemd128,
+    Ripemd160 => ripemd::Ripemd160,
+    Ripemd256 => ripemd::Ripemd256,
+    Ripemd320 => ripemd::Ripemd320,
+    Sha1 => sha1::Sha1,
+    Sha2224 => sha2::Sha224,
+    Sha2256 => sha2::Sha256,
+    Sha2384 => sha2::Sha384,
+    Sha2512 => sha2::Sha512,
+    Sha2512224 => sha2::Sha512_224,
+    Sha2512256 => sha2::Sha512_256,
+    Sha3224 => sha3::Sha3_224,
+    Sha3256 => sha3::Sha3_256,
+    Sha3384 => sha3::Sha3_384,
+    Sha3512 => sha3::Sha3_512,
+}
+
+impl Hasher {
+    /// finalize the hash into a newly allocated Multihash
+    pub(crate) fn finalize(self) -> Multihash {
+        let codec = self.codec();
+        let mut hash = Vec::with_capacity(self.output_size());
+        self.finalize_into(&mut hash);
+        Multihash {
+            codec,
+            hash,
+            encoding: None,
+        }
+    }
+
+    /// feed everything from the reader into the hasher
+    pub(crate) fn update_reader(&mut self, reader: impl Read) -> Result<(), Error> {
+        read_chunks(reader, |chunk| self.update(chunk))
+    }
+
+    /// feed the contents of a file into the hasher. blake3 memory maps large
+    /// regular files, hashing them on multiple threads with the rayon feature,
+    /// everything else is read through a buffer
+    pub(crate) fn update_file(&mut self, path: &Path) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "rayon")]
+            Hasher::Blake3(h) => {
+                h.update_mmap_rayon(path)?;
+            }
+            #[cfg(not(feature = "rayon"))]
+            Hasher::Blake3(h) => {
+                h.update_mmap(path)?;
+            }
+            _ => self.update_reader(File::open(path)?)?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mh::HASH_CODECS, Builder};
+
+    #[test]
+    fn test_all_hash_codecs() {
+        for codec in HASH_CODECS {
+            let hasher = Hasher::new(codec).unwrap();
+            assert_eq!(codec, hasher.codec());
+            let mh = hasher.finalize();
+            assert_eq!(mh.hash.len(), Hasher::new(codec).unwrap().output_size());
+        }
+    }
+
+    #[test]
+    fn test_unsupported() {
+        assert!(matches!(
+            Hasher::new(Codec::Identity),
+            Err(Error::UnsupportedHash(Codec::Identity))
+        ));
+    }
+
+    #[test]
+    fn test_incremental() {
+        let mut hasher = Hasher::new(Codec::Sha2256).unwrap();
+        hasher.update(b"for great justice, ");
+        hasher.update(b"move every zig!");
+        let mh1 = hasher.finalize();
+        let mh2 = Builder::new_from_bytes(Codec::Sha2256, b"for great justice, move every zig!")
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_eq!(mh1, mh2);
+    }
+}
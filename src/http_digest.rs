@@ -0,0 +1,225 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! RFC 9530 digest fields. `Content-Digest` and `Repr-Digest` share the same
+//! structured field dictionary syntax, e.g. `sha-256=:<base64>:`, as do the
+//! `Want-Content-Digest` and `Want-Repr-Digest` preference fields, e.g.
+//! `sha-512=3, sha-256=10`.
+use crate::{Error, Multihash};
+use multibase::Base;
+use multicodec::Codec;
+use multiutil::CodecInfo;
+
+/// the HTTP digest algorithms from the IANA registry that map to hash codecs.
+/// md5 and sha are deprecated but still parsed
+pub const HTTP_DIGEST_ALGORITHMS: [(Codec, &str); 4] = [
+    (Codec::Sha2256, "sha-256"),
+    (Codec::Sha2512, "sha-512"),
+    (Codec::Md5, "md5"),
+    (Codec::Sha1, "sha"),
+];
+
+/// the HTTP digest algorithm name of the codec
+pub fn algorithm_name(codec: Codec) -> Option<&'static str> {
+    HTTP_DIGEST_ALGORITHMS
+        .iter()
+        .find(|(c, _)| *c == codec)
+        .map(|(_, n)| *n)
+}
+
+/// the codec of the HTTP digest algorithm name
+pub fn algorithm_codec(name: &str) -> Option<Codec> {
+    HTTP_DIGEST_ALGORITHMS
+        .iter()
+        .find(|(_, n)| *n == name)
+        .map(|(c, _)| *c)
+}
+
+/// split a structured field dictionary into its keys and values, dropping
+/// any parameters on the values
+fn members(field: &str) -> Result<Vec<(&str, &str)>, Error> {
+    field
+        .split(',')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(|m| {
+            let m = m.split(';').next().unwrap_or_default();
+            let (key, value) = m
+                .split_once('=')
+                .ok_or_else(|| Error::InvalidDigestField(format!("missing value in '{}'", m)))?;
+            let valid = key.starts_with(|c: char| c.is_ascii_lowercase() || c == '*')
+                && key.chars().all(|c| {
+                    c.is_ascii_lowercase() || c.is_ascii_digit() || "_-.*".contains(c)
+                });
+            if !valid {
+                return Err(Error::InvalidDigestField(format!("invalid key '{}'", key)));
+            }
+            Ok((key, value.trim()))
+        })
+        .collect()
+}
+
+/// format the multihashes as a `Content-Digest` or `Repr-Digest` field value.
+/// the field has no way to say a digest is truncated so a truncated digest is
+/// an error
+pub fn format_digest<'a>(mhs: impl IntoIterator<Item = &'a Multihash>) -> Result<String, Error> {
+    let members = mhs
+        .into_iter()
+        .map(|mh| {
+            let name = algorithm_name(mh.codec()).ok_or(Error::UnsupportedHash(mh.codec()))?;
+            mh.check_full_digest()?;
+            Ok(format!("{}=:{}:", name, Base::Base64Pad.encode(mh.as_ref())))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok(members.join(", "))
+}
+
+/// parse a `Content-Digest` or `Repr-Digest` field value into multihashes.
+/// members with unknown algorithms are ignored as the RFC requires
+pub fn parse_digest(field: &str) -> Result<Vec<Multihash>, Error> {
+    let mut mhs = Vec::default();
+    for (key, value) in members(field)? {
+        let b64 = value
+            .strip_prefix(':')
+            .and_then(|v| v.strip_suffix(':'))
+            .ok_or_else(|| Error::InvalidDigestField(format!("'{}' isn't a byte sequence", value)))?;
+        let Some(codec) = algorithm_codec(key) else {
+            continue;
+        };
+        let digest = Base::Base64
+            .decode(b64.trim_end_matches('='))
+            .map_err(|e| Error::InvalidDigestField(e.to_string()))?;
+        mhs.push(Multihash::from_digest(codec, digest)?);
+    }
+    Ok(mhs)
+}
+
+/// format the codecs and their preferences, from 0 to 10, as a
+/// `Want-Content-Digest` or `Want-Repr-Digest` field value
+pub fn format_want_digest(prefs: &[(Codec, u8)]) -> Result<String, Error> {
+    let members = prefs
+        .iter()
+        .map(|(codec, pref)| {
+            let name = algorithm_name(*codec).ok_or(Error::UnsupportedHash(*codec))?;
+            if *pref > 10 {
+                return Err(Error::InvalidDigestField(format!(
+                    "invalid preference '{}'",
+                    pref
+                )));
+            }
+            Ok(format!("{}={}", name, pref))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok(members.join(", "))
+}
+
+/// parse a `Want-Content-Digest` or `Want-Repr-Digest` field value into the
+/// codecs in order of preference, most preferred first, which can be used as
+/// a [`crate::set::Policy::Trusted`] list. algorithms with a preference of 0
+/// and unknown algorithms are left out
+pub fn parse_want_digest(field: &str) -> Result<Vec<Codec>, Error> {
+    let mut prefs = Vec::default();
+    for (key, value) in members(field)? {
+        // the preference is a structured field integer, so only digits and
+        // no sign, from 0 to 10
+        let pref: u8 = Some(value)
+            .filter(|v| !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|v| v.parse().ok())
+            .filter(|p| *p <= 10)
+            .ok_or_else(|| Error::InvalidDigestField(format!("invalid preference '{}'", value)))?;
+        if let Some(codec) = algorithm_codec(key) {
+            if pref > 0 {
+                prefs.push((codec, pref));
+            }
+        }
+    }
+    // the sort is stable so equal preferences keep the field order
+    prefs.sort_by_key(|(_, pref)| core::cmp::Reverse(*pref));
+    Ok(prefs.into_iter().map(|(c, _)| c).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the example from RFC 9530 appendix B.1
+    const DATA: &[u8] = b"{\"hello\": \"world\"}";
+    const SHA256: &str = "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:";
+
+    #[test]
+    fn test_digest_roundtrip() {
+        let mhs = vec![
+            Multihash::of_bytes(Codec::Sha2256, DATA).unwrap(),
+            Multihash::of_bytes(Codec::Sha2512, DATA).unwrap(),
+        ];
+        let field = format_digest(&mhs).unwrap();
+        assert!(field.starts_with(SHA256));
+        assert_eq!(parse_digest(&field).unwrap(), mhs);
+    }
+
+    #[test]
+    fn test_parse_digest() {
+        let field = format!("unixsum=:AAAA:, {};foo=1 ,", SHA256);
+        assert_eq!(
+            parse_digest(&field).unwrap(),
+            vec![Multihash::of_bytes(Codec::Sha2256, DATA).unwrap()]
+        );
+        assert!(matches!(
+            parse_digest("sha-256=abc"),
+            Err(Error::InvalidDigestField(_))
+        ));
+        assert!(matches!(
+            parse_digest("SHA-256=:AAAA:"),
+            Err(Error::InvalidDigestField(_))
+        ));
+        assert_eq!(
+            parse_digest("sha-256=:AAAA:"),
+            Err(Error::InvalidDigestLength {
+                expected: 32,
+                len: 3
+            })
+        );
+        let mh = Multihash::of_bytes(Codec::Blake3, DATA).unwrap();
+        assert_eq!(format_digest([&mh]), Err(Error::UnsupportedHash(Codec::Blake3)));
+    }
+
+    #[test]
+    fn test_format_digest_truncated() {
+        let mh = Multihash::of_bytes(Codec::Sha2256, DATA).unwrap();
+        let truncated = Multihash {
+            codec: mh.codec(),
+            hash: mh.as_ref()[..16].to_vec(),
+            encoding: None,
+        };
+        assert_eq!(
+            format_digest([&mh, &truncated]),
+            Err(Error::InvalidDigestLength {
+                expected: 32,
+                len: 16
+            })
+        );
+    }
+
+    #[test]
+    fn test_want_digest() {
+        let field = format_want_digest(&[(Codec::Sha2256, 1), (Codec::Sha2512, 3)]).unwrap();
+        assert_eq!(field, "sha-256=1, sha-512=3");
+        assert_eq!(
+            parse_want_digest(&field).unwrap(),
+            vec![Codec::Sha2512, Codec::Sha2256]
+        );
+        assert_eq!(
+            parse_want_digest("md5=0, unixsum=5, sha=2").unwrap(),
+            vec![Codec::Sha1]
+        );
+        for field in ["sha-256=11", "sha-256=+5", "sha-256=-0", "sha-256="] {
+            assert!(matches!(
+                parse_want_digest(field),
+                Err(Error::InvalidDigestField(_))
+            ));
+        }
+        assert!(matches!(
+            format_want_digest(&[(Codec::Sha2256, 11)]),
+            Err(Error::InvalidDigestField(_))
+        ));
+        assert_eq!(format_want_digest(&[(Codec::Sha2256, 0)]).unwrap(), "sha-256=0");
+    }
+}
@@ -0,0 +1,108 @@
+// SPDX-License-Idnetifier: Apache-2.0
+use crate::{Error, Multihash};
+use multicodec::Codec;
+use multitrait::TryDecodeFrom;
+
+impl Multihash {
+    /// the order preserving key encoding of this multihash for use in ordered
+    /// key-value stores. the key is the numeric value of the codec as a single
+    /// byte count followed by that many big-endian bytes, with no leading zero
+    /// bytes, followed by the digest bytes. the digest isn't length prefixed
+    /// since the key-value store delimits the key. the lexicographic order of
+    /// the keys is by the numeric value of the codec and then by digest, which
+    /// isn't the [`Ord`] order of multihashes since that follows the order of
+    /// the codecs in [`Codec`]. all of the keys for a codec start with
+    /// [`Multihash::key_prefix`]
+    pub fn to_key(&self) -> Vec<u8> {
+        let mut key = Self::key_prefix(self.codec);
+        key.extend_from_slice(&self.hash);
+        key
+    }
+
+    /// the prefix shared by the keys of all multihashes with the given codec
+    pub fn key_prefix(codec: Codec) -> Vec<u8> {
+        let code = codec.code().to_be_bytes();
+        let skip = code.iter().take_while(|b| **b == 0).count();
+        let mut prefix = Vec::with_capacity(1 + code.len() - skip);
+        prefix.push((code.len() - skip) as u8);
+        prefix.extend_from_slice(&code[skip..]);
+        prefix
+    }
+
+    /// decode a multihash from its key encoding
+    pub fn from_key(key: &[u8]) -> Result<Self, Error> {
+        let (n, rest) = key.split_first().ok_or(Error::InvalidKey)?;
+        let n = *n as usize;
+        // the codec value must be minimally encoded for keys to be unique
+        if n > 8 || rest.len() < n || (n > 0 && rest[0] == 0) {
+            return Err(Error::InvalidKey);
+        }
+        let mut code = [0u8; 8];
+        code[8 - n..].copy_from_slice(&rest[..n]);
+        let code = u64::from_be_bytes(code);
+
+        // look up the codec from its varint encoding
+        let mut buf = unsigned_varint::encode::u64_buffer();
+        let (codec, _) = Codec::try_decode_from(unsigned_varint::encode::u64(code, &mut buf))
+            .map_err(|_| Error::UnknownCodec { offset: 1, code })?;
+        Ok(Self {
+            codec,
+            hash: rest[n..].to_vec(),
+            encoding: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mh::HASH_CODECS;
+
+    #[test]
+    fn test_key_roundtrip() {
+        for codec in HASH_CODECS {
+            let mh = Multihash::of_bytes(codec, b"multihash").unwrap();
+            let key = mh.to_key();
+            assert!(key.starts_with(&Multihash::key_prefix(codec)));
+            assert_eq!(mh, Multihash::from_key(&key).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_key_prefix() {
+        assert_eq!(Multihash::key_prefix(Codec::Identity), vec![0x00]);
+        assert_eq!(Multihash::key_prefix(Codec::Sha2256), vec![0x01, 0x12]);
+        assert_eq!(
+            Multihash::key_prefix(Codec::Blake2S256),
+            vec![0x02, 0xb2, 0x60]
+        );
+    }
+
+    #[test]
+    fn test_key_order() {
+        let mut mhs: Vec<Multihash> = HASH_CODECS
+            .iter()
+            .flat_map(|c| {
+                [b"a".as_slice(), b"b", b"c"]
+                    .into_iter()
+                    .map(move |d| Multihash::of_bytes(*c, d).unwrap())
+            })
+            .collect();
+        mhs.push(Multihash::default());
+        let mut keys: Vec<Vec<u8>> = mhs.iter().map(|mh| mh.to_key()).collect();
+        mhs.sort_by(|a, b| (a.codec.code(), &a.hash).cmp(&(b.codec.code(), &b.hash)));
+        keys.sort();
+        let sorted: Vec<Multihash> = keys
+            .iter()
+            .map(|k| Multihash::from_key(k).unwrap())
+            .collect();
+        assert_eq!(mhs, sorted);
+    }
+
+    #[test]
+    fn test_invalid_key() {
+        for key in [&[][..], &[0x09], &[0x02, 0x12], &[0x02, 0x00, 0x12]] {
+            assert!(matches!(Multihash::from_key(key), Err(Error::InvalidKey)));
+        }
+    }
+}
@@ -12,10 +12,65 @@
 pub mod error;
 pub use error::Error;
 
+/// Statically dispatched hashers for the supported hash codecs
+mod hasher;
+
+/// Content identifiers built from a content codec and a Multihash
+pub mod content_id;
+pub use content_id::Cid;
+
+/// ASN.1 object identifiers and PKCS#1 DigestInfo for Multihash
+pub mod asn1;
+
+/// BuildHasher for maps and sets keyed by Multihash that reuses the digest
+pub mod build_hasher;
+pub use build_hasher::MultihashBuildHasher;
+
+/// Order preserving key encoding of Multihash for key-value stores
+mod key;
+
 /// Multihash type and functions
 pub mod mh;
 pub use mh::{HASH_CODECS, SAFE_HASH_CODECS, Builder, EncodedMultihash, Multihash};
 
+/// COSE and JOSE hash algorithm identifiers and COSE_Hash for Multihash
+pub mod cose;
+
+/// in-toto, SPDX and CycloneDX digest maps for MultihashSet
+pub mod digest_set;
+pub use digest_set::DigestFormat;
+
+/// SSH, X.509 and grouped hex fingerprints for Multihash
+pub mod fingerprint;
+
+/// HTTP digest fields (RFC 9530) for Multihash
+pub mod http_digest;
+
+/// Hashing of the same input with several codecs in one pass
+pub mod multihasher;
+pub use multihasher::MultiHasher;
+
+/// Conversions to and from the multiformats multihash and cid crates
+#[cfg(feature = "multiformats")]
+pub mod multiformats;
+
+/// Named information (RFC 6920) URIs for Multihash
+pub mod ni;
+
+/// OCI content digests for Multihash
+pub mod oci;
+
+/// Streaming reading and writing of sequences of multihashes
+pub mod stream;
+pub use stream::{MultihashReader, MultihashWriter};
+
+/// Sets of multihashes of the same data
+pub mod set;
+pub use set::MultihashSet;
+
+/// Subresource Integrity strings for Multihash
+pub mod sri;
+
 /// Serde serialization for Multihash
 #[cfg(feature = "serde")]
 pub mod serde;
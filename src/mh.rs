@@ -1,12 +1,21 @@
 // SPDX-License-Idnetifier: Apache-2.0
-use crate::Error;
-use core::fmt;
-use digest::{Digest, DynDigest};
+use crate::{
+    build_hasher::MULTIHASH_MARKER,
+    hasher::{digest_len, Hasher},
+    Error,
+};
+use core::{fmt, str::FromStr};
 use multibase::Base;
 use multicodec::Codec;
 use multitrait::{Null, TryDecodeFrom};
-use multiutil::{BaseEncoded, CodecInfo, DetectedEncoder, EncodingInfo, Varbytes};
-use typenum::consts::*;
+use multiutil::{BaseEncoded, CodecInfo, DetectedEncoder, EncodingInfo};
+use std::{
+    io::{Read, Write},
+    path::Path,
+};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 /// the hash codecs currently supported
 pub const HASH_CODECS: [Codec; 23] = [
@@ -52,13 +61,174 @@ pub const SIGIL: Codec = Codec::Multihash;
 pub type EncodedMultihash = BaseEncoded<Multihash, DetectedEncoder>;
 
 /// inner implementation of the multihash
-#[derive(Clone, Default, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Default)]
 pub struct Multihash {
     /// hash codec
     pub(crate) codec: Codec,
 
     /// hash value
     pub(crate) hash: Vec<u8>,
+
+    /// the base encoding to use when encoded as a string, this is not part of
+    /// the identity of the multihash and is ignored when comparing
+    pub(crate) encoding: Option<Base>,
+}
+
+impl Multihash {
+    /// the length in bytes of the binary encoding of this multihash
+    pub fn encoded_len(&self) -> usize {
+        let mut codec_buf = unsigned_varint::encode::u64_buffer();
+        let mut len_buf = unsigned_varint::encode::usize_buffer();
+        unsigned_varint::encode::u64(self.codec.code(), &mut codec_buf).len()
+            + unsigned_varint::encode::usize(self.hash.len(), &mut len_buf).len()
+            + self.hash.len()
+    }
+
+    /// write the binary encoding of this multihash into the start of the
+    /// buffer without allocating, returning the number of bytes written
+    pub fn encode_to_slice(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let needed = self.encoded_len();
+        if buf.len() < needed {
+            return Err(Error::BufferTooSmall {
+                needed,
+                available: buf.len(),
+            });
+        }
+        let mut codec_buf = unsigned_varint::encode::u64_buffer();
+        let mut len_buf = unsigned_varint::encode::usize_buffer();
+        let mut i = 0;
+        // add in the hash codec, the length of the hash and the hash data
+        for part in [
+            unsigned_varint::encode::u64(self.codec.code(), &mut codec_buf),
+            unsigned_varint::encode::usize(self.hash.len(), &mut len_buf),
+            self.hash.as_slice(),
+        ] {
+            buf[i..i + part.len()].copy_from_slice(part);
+            i += part.len();
+        }
+        Ok(i)
+    }
+
+    /// write the binary encoding of this multihash to the writer without
+    /// allocating, returning the number of bytes written
+    pub fn write_to(&self, w: &mut impl Write) -> Result<usize, Error> {
+        let mut codec_buf = unsigned_varint::encode::u64_buffer();
+        let mut len_buf = unsigned_varint::encode::usize_buffer();
+        let codec = unsigned_varint::encode::u64(self.codec.code(), &mut codec_buf);
+        let len = unsigned_varint::encode::usize(self.hash.len(), &mut len_buf);
+        w.write_all(codec)?;
+        w.write_all(len)?;
+        w.write_all(&self.hash)?;
+        Ok(codec.len() + len.len() + self.hash.len())
+    }
+
+    /// set the base encoding used when this multihash is encoded as a string
+    pub fn with_encoding(mut self, base: Base) -> Self {
+        self.encoding = Some(base);
+        self
+    }
+
+    /// set the base encoding used when this multihash is encoded as a string
+    pub fn set_encoding(&mut self, base: Base) {
+        self.encoding = Some(base);
+    }
+
+    /// wrap a digest calculated elsewhere, checking that the codec is a
+    /// supported hash codec and that the digest is the right length for it
+    pub(crate) fn from_digest(codec: Codec, digest: Vec<u8>) -> Result<Self, Error> {
+        let mh = Self {
+            codec,
+            hash: digest,
+            encoding: None,
+        };
+        mh.check_full_digest()?;
+        Ok(mh)
+    }
+
+    /// check that the codec is a supported hash codec and that the digest is
+    /// the full length for it, formats without a way to say a digest is
+    /// truncated must not be given a truncated one
+    pub(crate) fn check_full_digest(&self) -> Result<(), Error> {
+        let expected = digest_len(self.codec).ok_or(Error::UnsupportedHash(self.codec))?;
+        if self.hash.len() != expected {
+            return Err(Error::InvalidDigestLength {
+                expected,
+                len: self.hash.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// hash the data with the given codec directly into a new Multihash,
+    /// avoiding the intermediate copies made by the [`Builder`]
+    pub fn of_bytes(codec: Codec, bytes: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let mut hasher = Hasher::new(codec)?;
+        hasher.update(bytes.as_ref());
+        Ok(hasher.finalize())
+    }
+
+    /// hash each of the inputs with the given codec, returning the multihashes
+    /// in the same order as the inputs. with the `rayon` feature enabled the
+    /// inputs are hashed in parallel on the rayon thread pool
+    pub fn of_all<B>(codec: Codec, inputs: &[B]) -> Result<Vec<Self>, Error>
+    where
+        B: AsRef<[u8]> + Sync,
+    {
+        let hasher = Hasher::new(codec)?;
+        let hash = |b: &B| {
+            let mut h = hasher.clone();
+            h.update(b.as_ref());
+            h.finalize()
+        };
+        #[cfg(feature = "rayon")]
+        let mhs = inputs.par_iter().map(hash).collect();
+        #[cfg(not(feature = "rayon"))]
+        let mhs = inputs.iter().map(hash).collect();
+        Ok(mhs)
+    }
+
+    /// hash each of the inputs from the iterator with the given codec,
+    /// returning the multihashes in the same order as the inputs. with the
+    /// `rayon` feature enabled the inputs are collected and hashed in parallel
+    pub fn of_iter<I>(codec: Codec, inputs: I) -> Result<Vec<Self>, Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]> + Sync,
+    {
+        #[cfg(feature = "rayon")]
+        {
+            let inputs: Vec<I::Item> = inputs.into_iter().collect();
+            Self::of_all(codec, &inputs)
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            let hasher = Hasher::new(codec)?;
+            Ok(inputs
+                .into_iter()
+                .map(|b| {
+                    let mut h = hasher.clone();
+                    h.update(b.as_ref());
+                    h.finalize()
+                })
+                .collect())
+        }
+    }
+
+    /// hash everything read from the reader with the given codec
+    pub fn of_reader(codec: Codec, reader: impl Read) -> Result<Self, Error> {
+        let mut hasher = Hasher::new(codec)?;
+        hasher.update_reader(reader)?;
+        Ok(hasher.finalize())
+    }
+
+    /// hash the contents of the file at the given path with the given codec.
+    /// large files hashed with blake3 are memory mapped and, with the `rayon`
+    /// feature enabled, hashed on multiple threads
+    pub fn of_file(codec: Codec, path: impl AsRef<Path>) -> Result<Self, Error> {
+        let mut hasher = Hasher::new(codec)?;
+        hasher.update_file(path.as_ref())?;
+        Ok(hasher.finalize())
+    }
 }
 
 impl CodecInfo for Multihash {
@@ -79,21 +249,119 @@ impl EncodingInfo for Multihash {
     }
 
     fn encoding(&self) -> Base {
-        Self::preferred_encoding()
+        self.encoding.unwrap_or_else(Self::preferred_encoding)
+    }
+}
+
+impl PartialEq for Multihash {
+    fn eq(&self, other: &Self) -> bool {
+        self.codec == other.codec && self.hash == other.hash
+    }
+}
+
+impl Eq for Multihash {}
+
+/// Hashes the codec and the digest, consistent with equality. A marker is
+/// written with `write_u128`, then the codec with `write_u64`, the digest
+/// length with `write_usize` and the digest with a single `write` call.
+/// [`crate::MultihashBuildHasher`] follows exactly this order to reuse the
+/// digest bytes directly so keep the two in sync
+impl core::hash::Hash for Multihash {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        state.write_u128(MULTIHASH_MARKER);
+        state.write_u64(self.codec.code());
+        state.write_usize(self.hash.len());
+        state.write(&self.hash);
+    }
+}
+
+impl PartialOrd for Multihash {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Multihash {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.codec
+            .cmp(&other.codec)
+            .then_with(|| self.hash.cmp(&other.hash))
     }
 }
 
-impl Into<Vec<u8>> for Multihash {
-    fn into(self) -> Vec<u8> {
-        let mut v = Vec::default();
-        // add in the hash codec
-        v.append(&mut self.codec.clone().into());
-        // add in the hash data
-        v.append(&mut Varbytes(self.hash.clone()).into());
+impl From<&Multihash> for Vec<u8> {
+    fn from(mh: &Multihash) -> Self {
+        let mut v = vec![0u8; mh.encoded_len()];
+        mh.encode_to_slice(&mut v)
+            .expect("buffer is exactly the encoded length");
         v
     }
 }
 
+impl From<Multihash> for Vec<u8> {
+    fn from(mh: Multihash) -> Self {
+        (&mh).into()
+    }
+}
+
+/// decode a codec varint found at the given byte offset of the input
+pub(crate) fn decode_codec(bytes: &[u8], offset: usize) -> Result<(Codec, &[u8]), Error> {
+    let (code, _) = unsigned_varint::decode::u64(bytes).map_err(|e| match e {
+        unsigned_varint::decode::Error::Insufficient => Error::Truncated { offset },
+        _ => Error::InvalidVarint { offset },
+    })?;
+    Codec::try_decode_from(bytes).map_err(|_| Error::UnknownCodec { offset, code })
+}
+
+/// decode a digest length varint found at the given byte offset of the input
+pub(crate) fn decode_length(bytes: &[u8], offset: usize) -> Result<(usize, &[u8]), Error> {
+    unsigned_varint::decode::usize(bytes).map_err(|e| match e {
+        unsigned_varint::decode::Error::Insufficient => Error::Truncated { offset },
+        _ => Error::LengthOverflow { offset },
+    })
+}
+
+impl Multihash {
+    /// decode a multihash found at the given byte offset of a larger input so
+    /// that errors report offsets into the larger input
+    pub(crate) fn decode_at(bytes: &[u8], offset: usize) -> Result<(Self, &[u8]), Error> {
+        // decode the hashing codec
+        let (codec, ptr) = decode_codec(bytes, offset)?;
+        // decode the length of the hash
+        let (len, ptr) = decode_length(ptr, offset + bytes.len() - ptr.len())?;
+        // decode the hash bytes
+        if ptr.len() < len {
+            return Err(Error::TruncatedDigest {
+                offset: offset + bytes.len() - ptr.len(),
+                expected: len,
+                available: ptr.len(),
+            });
+        }
+        let (hash, ptr) = ptr.split_at(len);
+        Ok((
+            Self {
+                codec,
+                hash: hash.to_vec(),
+                encoding: None,
+            },
+            ptr,
+        ))
+    }
+
+    /// decode a multihash that must take up all of the bytes, unlike
+    /// `TryFrom<&[u8]>` which ignores anything after the multihash
+    pub fn decode_exact(bytes: &[u8]) -> Result<Self, Error> {
+        let (mh, ptr) = Self::try_decode_from(bytes)?;
+        if !ptr.is_empty() {
+            return Err(Error::TrailingData {
+                offset: bytes.len() - ptr.len(),
+                len: ptr.len(),
+            });
+        }
+        Ok(mh)
+    }
+}
+
 impl<'a> TryFrom<&'a [u8]> for Multihash {
     type Error = Error;
 
@@ -107,13 +375,7 @@ impl<'a> TryDecodeFrom<'a> for Multihash {
     type Error = Error;
 
     fn try_decode_from(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Self::Error> {
-        // decode the hashing codec
-        let (codec, ptr) = Codec::try_decode_from(bytes)?;
-        // decode the hash bytes
-        let (hash, ptr) = Varbytes::try_decode_from(ptr)?;
-        // pull the inner Vec<u8> out of Varbytes
-        let hash = hash.to_inner();
-        Ok((Self { codec, hash }, ptr))
+        Self::decode_at(bytes, 0)
     }
 }
 
@@ -135,6 +397,32 @@ impl Null for Multihash {
     }
 }
 
+/// Displays the multihash as a multibase string using the multihash's base
+/// encoding, which is [`Base::Base16Lower`] unless it was set otherwise
+impl fmt::Display for Multihash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", EncodedMultihash::new(self.encoding(), self.clone()))
+    }
+}
+
+/// Parses a multihash from a multibase string in any base encoding,
+/// remembering the base encoding of the string
+impl FromStr for Multihash {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (base, v) = multibase::decode(s).map_err(|e| Error::InvalidMultibase(e.to_string()))?;
+        Ok(Self::try_from(v.as_slice())?.with_encoding(base))
+    }
+}
+
+impl From<EncodedMultihash> for Multihash {
+    fn from(emh: EncodedMultihash) -> Self {
+        let base = emh.encoding();
+        emh.to_inner().with_encoding(base)
+    }
+}
+
 impl fmt::Debug for Multihash {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -166,36 +454,11 @@ impl Builder {
 
     /// create a new builder from a hash
     pub fn new_from_bytes(codec: Codec, bytes: impl AsRef<[u8]>) -> Result<Self, Error> {
-        let mut hasher: Box<dyn DynDigest> = match codec {
-            Codec::Blake2B224 => Box::new(blake2::Blake2b::<U28>::new()),
-            Codec::Blake2B256 => Box::new(blake2::Blake2b::<U32>::new()),
-            Codec::Blake2B384 => Box::new(blake2::Blake2b::<U48>::new()),
-            Codec::Blake2B512 => Box::new(blake2::Blake2b::<U64>::new()),
-            Codec::Blake2S224 => Box::new(blake2::Blake2s::<U28>::new()),
-            Codec::Blake2S256 => Box::new(blake2::Blake2s::<U32>::new()),
-            Codec::Blake3 => Box::new(blake3::Hasher::new()),
-            Codec::Md5 => Box::new(md5::Md5::new()),
-            Codec::Ripemd128 => Box::new(ripemd::Ripemd128::new()),
-            Codec::Ripemd160 => Box::new(ripemd::Ripemd160::new()),
-            Codec::Ripemd256 => Box::new(ripemd::Ripemd256::new()),
-            Codec::Ripemd320 => Box::new(ripemd::Ripemd320::new()),
-            Codec::Sha1 => Box::new(sha1::Sha1::new()),
-            Codec::Sha2224 => Box::new(sha2::Sha224::new()),
-            Codec::Sha2256 => Box::new(sha2::Sha256::new()),
-            Codec::Sha2384 => Box::new(sha2::Sha384::new()),
-            Codec::Sha2512 => Box::new(sha2::Sha512::new()),
-            Codec::Sha2512224 => Box::new(sha2::Sha512_224::new()),
-            Codec::Sha2512256 => Box::new(sha2::Sha512_256::new()),
-            Codec::Sha3224 => Box::new(sha3::Sha3_224::new()),
-            Codec::Sha3256 => Box::new(sha3::Sha3_256::new()),
-            Codec::Sha3384 => Box::new(sha3::Sha3_384::new()),
-            Codec::Sha3512 => Box::new(sha3::Sha3_512::new()),
-            _ => return Err(Error::UnsupportedHash(codec)),
-        };
+        let mut hasher = Hasher::new(codec)?;
 
         // hash the data
         hasher.update(bytes.as_ref());
-        let hash = hasher.finalize().to_vec();
+        let hash = hasher.finalize().hash;
         Ok(Self {
             codec,
             hash: Some(hash),
@@ -203,6 +466,16 @@ impl Builder {
         })
     }
 
+    /// create a new builder from the contents of a file
+    pub fn new_from_path(codec: Codec, path: impl AsRef<Path>) -> Result<Self, Error> {
+        let mh = Multihash::of_file(codec, path)?;
+        Ok(Self {
+            codec,
+            hash: Some(mh.hash),
+            base_encoding: None,
+        })
+    }
+
     /// set the hash data
     pub fn with_hash(mut self, hash: impl Into<Vec<u8>>) -> Self {
         self.hash = Some(hash.into());
@@ -217,11 +490,8 @@ impl Builder {
 
     /// build a base encoded multihash
     pub fn try_build_encoded(&self) -> Result<EncodedMultihash, Error> {
-        Ok(BaseEncoded::new(
-            self.base_encoding
-                .unwrap_or_else(|| Multihash::preferred_encoding()),
-            self.try_build()?,
-        ))
+        let mh = self.try_build()?;
+        Ok(BaseEncoded::new(mh.encoding(), mh))
     }
 
     /// build the multihash by hashing the provided data
@@ -229,6 +499,7 @@ impl Builder {
         Ok(Multihash {
             codec: self.codec,
             hash: self.hash.clone().ok_or_else(|| Error::MissingHash)?,
+            encoding: self.base_encoding,
         })
     }
 }
@@ -315,6 +586,34 @@ mod tests {
         assert_eq!(mh1, mh2);
     }
 
+    #[test]
+    fn test_encode_by_reference() {
+        let mh = Multihash::of_bytes(Codec::Blake2S256, b"multihash").unwrap();
+        let v1: Vec<u8> = (&mh).into();
+        assert_eq!(v1.len(), mh.encoded_len());
+        // 3 byte codec varint, 1 byte length varint, 32 byte digest
+        assert_eq!(mh.encoded_len(), 36);
+
+        let mut buf = [0xffu8; 40];
+        assert_eq!(mh.encode_to_slice(&mut buf).unwrap(), 36);
+        assert_eq!(&buf[..36], v1.as_slice());
+        assert_eq!(buf[36..], [0xff; 4]);
+        match mh.encode_to_slice(&mut buf[..35]) {
+            Err(Error::BufferTooSmall { needed, available }) => {
+                assert_eq!(needed, 36);
+                assert_eq!(available, 35);
+            }
+            _ => panic!("expected buffer too small"),
+        }
+
+        let mut w = Vec::default();
+        assert_eq!(mh.write_to(&mut w).unwrap(), 36);
+        assert_eq!(w, v1);
+
+        let v2: Vec<u8> = mh.into();
+        assert_eq!(v1, v2);
+    }
+
     #[test]
     fn test_encoded() {
         let mh = Builder::new_from_bytes(Codec::Sha3256, b"for great justice, move every zig!")
@@ -338,6 +637,179 @@ mod tests {
         assert_eq!(mh1, mh2);
     }
 
+    #[test]
+    fn test_of_bytes() {
+        let mh1 = Multihash::of_bytes(Codec::Sha3256, b"for great justice, move every zig!").unwrap();
+        let mh2 = Builder::new_from_bytes(Codec::Sha3256, b"for great justice, move every zig!")
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_eq!(mh1, mh2);
+        assert!(Multihash::of_bytes(Codec::Identity, b"multihash").is_err());
+    }
+
+    #[test]
+    fn test_of_all() {
+        let inputs: Vec<Vec<u8>> = (0..1000u32).map(|i| i.to_be_bytes().to_vec()).collect();
+        let mhs = Multihash::of_all(Codec::Blake2B256, &inputs).unwrap();
+        assert_eq!(mhs.len(), inputs.len());
+        for (mh, input) in mhs.iter().zip(inputs.iter()) {
+            assert_eq!(*mh, Multihash::of_bytes(Codec::Blake2B256, input).unwrap());
+        }
+        let mhs2 = Multihash::of_iter(Codec::Blake2B256, inputs.iter()).unwrap();
+        assert_eq!(mhs, mhs2);
+        assert!(Multihash::of_all(Codec::Identity, &inputs).is_err());
+        assert!(Multihash::of_iter(Codec::Sha2256, Vec::<&[u8]>::new())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_of_file() {
+        // larger than the blake3 memory mapping threshold
+        let data: Vec<u8> = (0..256 * 1024).map(|i| (i % 251) as u8).collect();
+        let path = std::env::temp_dir().join(format!("multihash-test-{}", std::process::id()));
+        std::fs::write(&path, &data).unwrap();
+        for codec in [Codec::Blake3, Codec::Sha2256, Codec::Sha3512] {
+            let mh1 = Multihash::of_file(codec, &path).unwrap();
+            let mh2 = Multihash::of_bytes(codec, &data).unwrap();
+            assert_eq!(mh1, mh2);
+            let mh3 = Builder::new_from_path(codec, &path)
+                .unwrap()
+                .try_build()
+                .unwrap();
+            assert_eq!(mh1, mh3);
+            let mh4 = Multihash::of_reader(codec, data.as_slice()).unwrap();
+            assert_eq!(mh1, mh4);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_of_file_missing() {
+        let path = std::env::temp_dir().join("multihash-test-does-not-exist");
+        for codec in [Codec::Blake3, Codec::Sha2256] {
+            match Multihash::of_file(codec, &path) {
+                Err(Error::Io { kind, .. }) => assert_eq!(kind, std::io::ErrorKind::NotFound),
+                _ => panic!("expected an I/O error"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_encoding() {
+        let mh1 = Builder::new_from_bytes(Codec::Sha2256, b"multihash")
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_eq!(mh1.encoding(), Base::Base16Lower);
+        let mh2 = Builder::new_from_bytes(Codec::Sha2256, b"multihash")
+            .unwrap()
+            .with_base_encoding(Base::Base32Lower)
+            .try_build()
+            .unwrap();
+        assert_eq!(mh2.encoding(), Base::Base32Lower);
+        // the encoding isn't part of the identity
+        assert_eq!(mh1, mh2);
+        let emh: EncodedMultihash = mh2.into();
+        assert_eq!(emh.encoding(), Base::Base32Lower);
+        let mh3 = mh1.with_encoding(Base::Base58Btc);
+        assert_eq!(mh3.encoding(), Base::Base58Btc);
+    }
+
+    #[test]
+    fn test_display_fromstr() {
+        let mh1 = Multihash::of_bytes(Codec::Sha2256, b"multihash").unwrap();
+        let s = mh1.to_string();
+        assert_eq!(s, "f12209cbc07c3f991725836a3aa2a581ca2029198aa420b9d99bc0e131d9f3e2cbe47");
+        let mh2: Multihash = s.parse().unwrap();
+        assert_eq!(mh1, mh2);
+
+        let mh3: Multihash = "zQmYtUc4iTCbbfVSDNKvtQqrfyezPPnFvE33wFmutw9PBBk".parse().unwrap();
+        assert_eq!(mh1, mh3);
+        assert_eq!(mh3.encoding(), Base::Base58Btc);
+        assert_eq!(format!("{mh3}"), "zQmYtUc4iTCbbfVSDNKvtQqrfyezPPnFvE33wFmutw9PBBk");
+
+        let emh: EncodedMultihash = mh3.clone().into();
+        assert_eq!(emh.to_string(), mh3.to_string());
+        assert_eq!(Multihash::from(emh), mh3);
+
+        assert!("not a multihash".parse::<Multihash>().is_err());
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+        let mut set = HashSet::new();
+        set.insert(Multihash::of_bytes(Codec::Sha2256, b"multihash").unwrap());
+        set.insert(Multihash::of_bytes(Codec::Sha2512, b"multihash").unwrap());
+        // same multihash with a different encoding is the same key
+        assert!(!set.insert(
+            Multihash::of_bytes(Codec::Sha2256, b"multihash")
+                .unwrap()
+                .with_encoding(Base::Base58Btc)
+        ));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let v: Vec<u8> = Multihash::of_bytes(Codec::Blake2S256, b"multihash")
+            .unwrap()
+            .into();
+
+        // truncated in the codec varint
+        assert_eq!(
+            Multihash::try_from(&v[..2]),
+            Err(Error::Truncated { offset: 0 })
+        );
+        // truncated before the length
+        assert_eq!(
+            Multihash::try_from(&v[..3]),
+            Err(Error::Truncated { offset: 3 })
+        );
+        // truncated digest
+        assert_eq!(
+            Multihash::try_from(&v[..20]),
+            Err(Error::TruncatedDigest {
+                offset: 4,
+                expected: 32,
+                available: 16
+            })
+        );
+        // trailing data
+        let mut t = v.clone();
+        t.extend_from_slice(&[0, 0]);
+        assert_eq!(
+            Multihash::decode_exact(t.as_slice()),
+            Err(Error::TrailingData { offset: 36, len: 2 })
+        );
+        assert_eq!(
+            Multihash::try_from(t.as_slice()),
+            Multihash::try_from(v.as_slice())
+        );
+        // length overflow
+        let mut o = v[..3].to_vec();
+        o.extend_from_slice(&[0xff; 11]);
+        assert_eq!(
+            Multihash::try_from(o.as_slice()),
+            Err(Error::LengthOverflow { offset: 3 })
+        );
+        // unknown codec
+        assert_eq!(
+            Multihash::try_from(&[0xff, 0xff, 0x03, 0x00][..]),
+            Err(Error::UnknownCodec {
+                offset: 0,
+                code: 0xffff
+            })
+        );
+        // invalid multibase
+        assert!(matches!(
+            "!nope".parse::<Multihash>(),
+            Err(Error::InvalidMultibase(_))
+        ));
+    }
+
     #[test]
     fn test_null() {
         let mh1 = Multihash::null();
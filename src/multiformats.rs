@@ -0,0 +1,108 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! Conversions between [`Multihash`] and the fixed size `Multihash<S>` from
+//! the multiformats `multihash` crate and, with the `cid` feature, the
+//! multiformats `cid` crate. The conversions go through the binary encoding
+//! shared by both implementations.
+use crate::{Error, Multihash};
+use mf_multihash::Multihash as MfMultihash;
+
+impl<const S: usize> TryFrom<&Multihash> for MfMultihash<S> {
+    type Error = Error;
+
+    fn try_from(mh: &Multihash) -> Result<Self, Self::Error> {
+        if mh.hash.len() > S {
+            return Err(Error::DigestTooLarge {
+                len: mh.hash.len(),
+                max: S,
+            });
+        }
+        let v: Vec<u8> = mh.into();
+        MfMultihash::from_bytes(&v).map_err(|e| Error::Multiformats(e.to_string()))
+    }
+}
+
+impl<const S: usize> TryFrom<Multihash> for MfMultihash<S> {
+    type Error = Error;
+
+    fn try_from(mh: Multihash) -> Result<Self, Self::Error> {
+        Self::try_from(&mh)
+    }
+}
+
+// only by value, the multiformats types are Copy and an impl for a reference
+// would make `Multihash::try_from(v.as_ref())` ambiguous
+impl<const S: usize> TryFrom<MfMultihash<S>> for Multihash {
+    type Error = Error;
+
+    fn try_from(mh: MfMultihash<S>) -> Result<Self, Self::Error> {
+        Self::try_from(mh.to_bytes().as_slice())
+    }
+}
+
+#[cfg(feature = "cid")]
+mod cid_interop {
+    use crate::{Error, Multihash};
+    use cid::CidGeneric;
+    use multicodec::Codec;
+
+    impl<const S: usize> TryFrom<CidGeneric<S>> for Multihash {
+        type Error = Error;
+
+        fn try_from(cid: CidGeneric<S>) -> Result<Self, Self::Error> {
+            Self::try_from(*cid.hash())
+        }
+    }
+
+    impl Multihash {
+        /// create a multiformats CIDv1 for this multihash and the content codec
+        pub fn to_cid_v1<const S: usize>(&self, codec: Codec) -> Result<CidGeneric<S>, Error> {
+            Ok(CidGeneric::new_v1(codec.code(), self.try_into()?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multicodec::Codec;
+
+    #[test]
+    fn test_multihash_roundtrip() {
+        for codec in crate::mh::HASH_CODECS {
+            let mh1 = Multihash::of_bytes(codec, b"multihash").unwrap();
+            let mfmh: MfMultihash<64> = (&mh1).try_into().unwrap();
+            assert_eq!(mfmh.code(), codec.code());
+            assert_eq!(mfmh.digest(), mh1.as_ref());
+            let mh2 = Multihash::try_from(mfmh).unwrap();
+            assert_eq!(mh1, mh2);
+        }
+    }
+
+    #[test]
+    fn test_digest_too_large() {
+        let mh = Multihash::of_bytes(Codec::Sha2512, b"multihash").unwrap();
+        assert_eq!(
+            MfMultihash::<32>::try_from(&mh),
+            Err(Error::DigestTooLarge { len: 64, max: 32 })
+        );
+    }
+
+    #[test]
+    fn test_unknown_codec() {
+        let mfmh = MfMultihash::<64>::wrap(0x300000, b"multihash").unwrap();
+        assert!(matches!(
+            Multihash::try_from(mfmh),
+            Err(Error::UnknownCodec { offset: 0, .. })
+        ));
+    }
+
+    #[cfg(feature = "cid")]
+    #[test]
+    fn test_cid() {
+        let mh1 = Multihash::of_bytes(Codec::Sha2256, b"multihash").unwrap();
+        let cid: cid::Cid = mh1.to_cid_v1(Codec::Raw).unwrap();
+        assert_eq!(cid.codec(), Codec::Raw.code());
+        let mh2 = Multihash::try_from(cid).unwrap();
+        assert_eq!(mh1, mh2);
+    }
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Idnetifier: Apache-2.0
+use crate::{
+    hasher::{read_chunks, Hasher},
+    Error, Multihash,
+};
+use multicodec::Codec;
+use std::{fs::File, io, path::Path};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// the smallest chunk of data worth updating the hashers in parallel for
+pub const PARALLEL_MIN_LEN: usize = 64 * 1024;
+
+/// Hashes the same input with several hash codecs at once so that the input
+/// only has to be read once. It produces one Multihash per codec.
+#[derive(Clone)]
+pub struct MultiHasher {
+    hashers: Vec<Hasher>,
+}
+
+impl MultiHasher {
+    /// create a new multi-hasher for the given codecs. duplicate codecs are
+    /// ignored and the order of the codecs is the order of the results
+    pub fn new(codecs: impl IntoIterator<Item = Codec>) -> Result<Self, Error> {
+        let mut hashers: Vec<Hasher> = Vec::default();
+        for codec in codecs {
+            if !hashers.iter().any(|h| h.codec() == codec) {
+                hashers.push(Hasher::new(codec)?);
+            }
+        }
+        Ok(Self { hashers })
+    }
+
+    /// the codecs this multi-hasher produces multihashes for
+    pub fn codecs(&self) -> Vec<Codec> {
+        self.hashers.iter().map(|h| h.codec()).collect()
+    }
+
+    /// feed more data into all of the hashers. with the `rayon` feature
+    /// enabled the hashers are updated in parallel for chunks of at least
+    /// [`PARALLEL_MIN_LEN`] bytes, smaller writes such as those through
+    /// `io::Write` aren't worth the fork/join overhead
+    pub fn update(&mut self, data: &[u8]) {
+        #[cfg(feature = "rayon")]
+        {
+            if data.len() >= PARALLEL_MIN_LEN && self.hashers.len() > 1 {
+                self.hashers.par_iter_mut().for_each(|h| h.update(data));
+                return;
+            }
+        }
+        self.hashers.iter_mut().for_each(|h| h.update(data));
+    }
+
+    /// feed everything from the reader into all of the hashers
+    pub fn update_reader(&mut self, reader: impl io::Read) -> Result<(), Error> {
+        read_chunks(reader, |chunk| self.update(chunk))
+    }
+
+    /// feed the contents of the file into all of the hashers
+    pub fn update_file(&mut self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.update_reader(File::open(path)?)
+    }
+
+    /// finalize all of the hashes, returning one multihash per codec in the
+    /// order the codecs were given
+    pub fn finalize(self) -> Vec<Multihash> {
+        self.hashers.into_iter().map(|h| h.finalize()).collect()
+    }
+}
+
+impl io::Write for MultiHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multihasher() {
+        let codecs = [Codec::Sha2256, Codec::Sha3512, Codec::Blake3];
+        let mut mh = MultiHasher::new(codecs).unwrap();
+        mh.update(b"for great justice, ");
+        mh.update(b"move every zig!");
+        let mhs = mh.finalize();
+        assert_eq!(mhs.len(), codecs.len());
+        for (mh, codec) in mhs.iter().zip(codecs.iter()) {
+            assert_eq!(
+                *mh,
+                Multihash::of_bytes(*codec, b"for great justice, move every zig!").unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_multihasher_reader() {
+        let data: Vec<u8> = (0..200 * 1024).map(|i| (i % 251) as u8).collect();
+        let mut mh = MultiHasher::new([Codec::Sha1, Codec::Sha2256, Codec::Sha1]).unwrap();
+        assert_eq!(mh.codecs(), vec![Codec::Sha1, Codec::Sha2256]);
+        mh.update_reader(data.as_slice()).unwrap();
+        let mhs = mh.finalize();
+        assert_eq!(mhs[0], Multihash::of_bytes(Codec::Sha1, &data).unwrap());
+        assert_eq!(mhs[1], Multihash::of_bytes(Codec::Sha2256, &data).unwrap());
+    }
+
+    #[test]
+    fn test_multihasher_unsupported() {
+        assert!(MultiHasher::new([Codec::Sha2256, Codec::Identity]).is_err());
+    }
+}
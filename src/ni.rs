@@ -0,0 +1,230 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! RFC 6920 named information `ni:` URIs, e.g. `ni:///sha-256;<base64url>`,
+//! and their human readable `nih:` form, e.g. `nih:sha-256-120;<hex>;<check>`.
+//! The truncated suites are multihashes with a truncated digest.
+use crate::{Error, Multihash};
+use multibase::Base;
+use multicodec::Codec;
+
+/// the suites in the IANA named information hash algorithm registry as the
+/// suite id, the name, the hash codec and the length of the digest
+pub const NI_SUITES: [(u8, &str, Codec, usize); 15] = [
+    (1, "sha-256", Codec::Sha2256, 32),
+    (2, "sha-256-128", Codec::Sha2256, 16),
+    (3, "sha-256-120", Codec::Sha2256, 15),
+    (4, "sha-256-96", Codec::Sha2256, 12),
+    (5, "sha-256-64", Codec::Sha2256, 8),
+    (6, "sha-256-32", Codec::Sha2256, 4),
+    (7, "sha-384", Codec::Sha2384, 48),
+    (8, "sha-512", Codec::Sha2512, 64),
+    (9, "sha3-224", Codec::Sha3224, 28),
+    (10, "sha3-256", Codec::Sha3256, 32),
+    (11, "sha3-384", Codec::Sha3384, 48),
+    (12, "sha3-512", Codec::Sha3512, 64),
+    (13, "blake2s-256", Codec::Blake2S256, 32),
+    (14, "blake2b-256", Codec::Blake2B256, 32),
+    (15, "blake2b-512", Codec::Blake2B512, 64),
+];
+
+/// the suite name for the codec and digest length
+pub fn suite_name(codec: Codec, len: usize) -> Option<&'static str> {
+    NI_SUITES
+        .iter()
+        .find(|(_, _, c, l)| *c == codec && *l == len)
+        .map(|(_, n, _, _)| *n)
+}
+
+/// the codec and digest length for the suite name or, in `nih:` URIs, the
+/// decimal suite id
+pub fn suite_codec(name: &str) -> Option<(Codec, usize)> {
+    NI_SUITES
+        .iter()
+        .find(|(id, n, _, _)| *n == name || id.to_string() == name)
+        .map(|(_, _, c, l)| (*c, *l))
+}
+
+/// the Luhn mod 16 check digit of a lowercase hex string
+fn check_digit(hex: &str) -> char {
+    let sum: u32 = hex
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(16))
+        .enumerate()
+        .map(|(i, d)| {
+            let d = if i % 2 == 0 { d * 2 } else { d };
+            d / 16 + d % 16
+        })
+        .sum();
+    char::from_digit((16 - sum % 16) % 16, 16).unwrap_or('0')
+}
+
+/// look up the suite and decode the digest, checking its length
+fn to_multihash(name: &str, digest: Vec<u8>) -> Result<Multihash, Error> {
+    let (codec, len) = suite_codec(name)
+        .ok_or_else(|| Error::InvalidNi(format!("unsupported suite '{}'", name)))?;
+    if digest.len() != len {
+        return Err(Error::InvalidDigestLength {
+            expected: len,
+            len: digest.len(),
+        });
+    }
+    Ok(Multihash {
+        codec,
+        hash: digest,
+        encoding: None,
+    })
+}
+
+impl Multihash {
+    /// the suite name of this multihash, truncated sha2-256 digests map to the
+    /// truncated suites
+    fn ni_suite(&self) -> Result<&'static str, Error> {
+        suite_name(self.codec, self.hash.len()).ok_or(Error::UnsupportedHash(self.codec))
+    }
+
+    /// the `ni:` URI of this multihash with an optional authority, e.g.
+    /// `ni:///sha-256;<base64url>`
+    pub fn to_ni(&self, authority: Option<&str>) -> Result<String, Error> {
+        Ok(format!(
+            "ni://{}/{};{}",
+            authority.unwrap_or_default(),
+            self.ni_suite()?,
+            Base::Base64Url.encode(&self.hash)
+        ))
+    }
+
+    /// the `nih:` URI of this multihash, with the check digit if asked for,
+    /// e.g. `nih:sha-256-120;<hex>;<check>`
+    pub fn to_nih(&self, check: bool) -> Result<String, Error> {
+        let hex = hex::encode(&self.hash);
+        let mut nih = format!("nih:{};{}", self.ni_suite()?, hex);
+        if check {
+            nih.push(';');
+            nih.push(check_digit(&hex));
+        }
+        Ok(nih)
+    }
+
+    /// parse an `ni:` URI, any authority and query parameters are ignored
+    pub fn from_ni(s: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidNi(format!("'{}' isn't an ni URI", s));
+        let rest = s
+            .get(..3)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("ni:"))
+            .map(|_| &s[3..])
+            .ok_or_else(invalid)?;
+        // skip the authority, if any, to get the path
+        let path = match rest.strip_prefix("//") {
+            Some(r) => r.find('/').map(|i| &r[i..]).ok_or_else(invalid)?,
+            None => rest,
+        };
+        let path = path.strip_prefix('/').ok_or_else(invalid)?;
+        let path = path.split('?').next().unwrap_or_default();
+        let (name, b64) = path.split_once(';').ok_or_else(invalid)?;
+        let digest = Base::Base64Url
+            .decode(b64)
+            .map_err(|e| Error::InvalidNi(e.to_string()))?;
+        to_multihash(name, digest)
+    }
+
+    /// parse an `nih:` URI, the digest hex may contain `-` separators and the
+    /// check digit is verified when present
+    pub fn from_nih(s: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidNi(format!("'{}' isn't an nih URI", s));
+        let rest = s
+            .get(..4)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("nih:"))
+            .map(|_| &s[4..])
+            .ok_or_else(invalid)?;
+        let mut parts = rest.split(';');
+        let name = parts.next().ok_or_else(invalid)?;
+        let hex = parts
+            .next()
+            .ok_or_else(invalid)?
+            .replace('-', "")
+            .to_lowercase();
+        if let Some(check) = parts.next() {
+            // the check digit is hex, so either case is accepted like the digest
+            if check.len() != 1 || !check.to_ascii_lowercase().starts_with(check_digit(&hex)) {
+                return Err(Error::InvalidNi(format!("bad check digit '{}'", check)));
+            }
+        }
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        let digest = hex::decode(hex).map_err(|e| Error::InvalidNi(e.to_string()))?;
+        to_multihash(name, digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiutil::CodecInfo;
+
+    // the examples from RFC 6920 section 8
+    const DATA: &[u8] = b"Hello World!";
+    const NI: &str = "ni:///sha-256;f4OxZX_x_FO5LcGBSKHWXfwtSx-j1ncoSt3SABJtkGk";
+    const NIH: &str = "nih:sha-256-120;5326-9057-e12f-e2b7-4ba0-7c89-2560-a2;f";
+
+    #[test]
+    fn test_ni_roundtrip() {
+        for (_, _, codec, _) in NI_SUITES.iter().filter(|(_, _, _, l)| *l >= 28) {
+            let mh = Multihash::of_bytes(*codec, DATA).unwrap();
+            let ni = mh.to_ni(Some("example.com")).unwrap();
+            assert!(ni.starts_with("ni://example.com/"));
+            assert_eq!(Multihash::from_ni(&ni).unwrap(), mh);
+            let nih = mh.to_nih(true).unwrap();
+            assert_eq!(Multihash::from_nih(&nih).unwrap(), mh);
+        }
+    }
+
+    #[test]
+    fn test_ni() {
+        let mh = Multihash::from_ni(NI).unwrap();
+        assert_eq!(mh.codec(), Codec::Sha2256);
+        assert_eq!(mh.to_ni(None).unwrap(), NI);
+        assert_eq!(
+            Multihash::from_ni(&format!("{}?ct=text/plain", NI)).unwrap(),
+            mh
+        );
+        assert!(matches!(
+            Multihash::from_ni("http://x"),
+            Err(Error::InvalidNi(_))
+        ));
+        assert!(matches!(
+            Multihash::from_ni("ni:///md5;AAAA"),
+            Err(Error::InvalidNi(_))
+        ));
+        assert_eq!(
+            Multihash::from_ni("ni:///sha-256-32;AAAA"),
+            Err(Error::InvalidDigestLength {
+                expected: 4,
+                len: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_nih_truncated() {
+        let mh = Multihash::from_nih(NIH).unwrap();
+        assert_eq!(mh.codec(), Codec::Sha2256);
+        assert_eq!(mh.as_ref().len(), 15);
+        assert_eq!(
+            mh.to_nih(true).unwrap(),
+            "nih:sha-256-120;53269057e12fe2b74ba07c892560a2;f"
+        );
+        assert_eq!(
+            Multihash::from_nih("nih:3;53269057e12fe2b74ba07c892560a2").unwrap(),
+            mh
+        );
+        assert_eq!(
+            Multihash::from_nih("nih:sha-256-120;53269057E12FE2B74BA07C892560A2;F").unwrap(),
+            mh
+        );
+        assert!(matches!(
+            Multihash::from_nih("nih:3;53269057e12fe2b74ba07c892560a2;0"),
+            Err(Error::InvalidNi(_))
+        ));
+    }
+}
@@ -0,0 +1,137 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! Conversion between [`Multihash`] and OCI content digests such as
+//! `sha256:<hex>` as defined by the OCI image spec.
+use crate::{Error, Multihash};
+use multicodec::Codec;
+
+/// the registered OCI digest algorithms
+pub const OCI_ALGORITHMS: [(Codec, &str); 2] =
+    [(Codec::Sha2256, "sha256"), (Codec::Sha2512, "sha512")];
+
+/// the OCI digest algorithm name of the codec
+pub fn algorithm_name(codec: Codec) -> Option<&'static str> {
+    OCI_ALGORITHMS
+        .iter()
+        .find(|(c, _)| *c == codec)
+        .map(|(_, n)| *n)
+}
+
+/// the codec of the OCI digest algorithm name
+pub fn algorithm_codec(name: &str) -> Option<Codec> {
+    OCI_ALGORITHMS
+        .iter()
+        .find(|(_, n)| *n == name)
+        .map(|(c, _)| *c)
+}
+
+/// true if the algorithm matches the digest grammar of the image spec:
+/// lowercase alphanumeric components joined by one of `+._-`
+fn valid_algorithm(algorithm: &str) -> bool {
+    algorithm.split(['+', '.', '_', '-']).all(|c| {
+        !c.is_empty()
+            && c.bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+    })
+}
+
+impl Multihash {
+    /// the OCI digest of this multihash, e.g. `sha256:<hex>`. the encoded
+    /// digest must be the full length for the algorithm so a truncated digest
+    /// is an error
+    pub fn to_oci_digest(&self) -> Result<String, Error> {
+        let name = algorithm_name(self.codec).ok_or(Error::UnsupportedHash(self.codec))?;
+        self.check_full_digest()?;
+        Ok(format!("{}:{}", name, hex::encode(&self.hash)))
+    }
+
+    /// parse an OCI digest, the algorithm must be registered and the encoded
+    /// digest must be lowercase hex of the right length
+    pub fn from_oci_digest(s: &str) -> Result<Self, Error> {
+        let (algorithm, encoded) = s
+            .split_once(':')
+            .ok_or_else(|| Error::InvalidOciDigest(format!("missing algorithm in '{}'", s)))?;
+        if !valid_algorithm(algorithm) {
+            return Err(Error::InvalidOciDigest(format!(
+                "invalid algorithm '{}'",
+                algorithm
+            )));
+        }
+        let codec = algorithm_codec(algorithm).ok_or_else(|| {
+            Error::InvalidOciDigest(format!("unregistered algorithm '{}'", algorithm))
+        })?;
+        if !encoded
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        {
+            return Err(Error::InvalidOciDigest(format!(
+                "'{}' isn't lowercase hex",
+                encoded
+            )));
+        }
+        let digest = hex::decode(encoded).map_err(|e| Error::InvalidOciDigest(e.to_string()))?;
+        Self::from_digest(codec, digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiutil::CodecInfo;
+
+    const SHA256: &str = "sha256:6c3c624b58dbbcd3c0dd82b4c53f04194d1247c6eebdaab7c610cf7d66709b3b";
+
+    #[test]
+    fn test_oci_roundtrip() {
+        for codec in [Codec::Sha2256, Codec::Sha2512] {
+            let mh = Multihash::of_bytes(codec, b"for great justice, move every zig!").unwrap();
+            let s = mh.to_oci_digest().unwrap();
+            assert_eq!(Multihash::from_oci_digest(&s).unwrap(), mh);
+        }
+        let mh = Multihash::from_oci_digest(SHA256).unwrap();
+        assert_eq!(mh.codec(), Codec::Sha2256);
+        assert_eq!(mh.to_oci_digest().unwrap(), SHA256);
+    }
+
+    #[test]
+    fn test_oci_truncated() {
+        let mh = Multihash::from_oci_digest(SHA256).unwrap();
+        let truncated = Multihash {
+            codec: mh.codec(),
+            hash: mh.as_ref()[..20].to_vec(),
+            encoding: None,
+        };
+        assert_eq!(
+            truncated.to_oci_digest(),
+            Err(Error::InvalidDigestLength {
+                expected: 32,
+                len: 20
+            })
+        );
+    }
+
+    #[test]
+    fn test_oci_errors() {
+        let mh = Multihash::of_bytes(Codec::Blake3, b"move every zig!").unwrap();
+        assert_eq!(
+            mh.to_oci_digest(),
+            Err(Error::UnsupportedHash(Codec::Blake3))
+        );
+        for s in [
+            "6c3c624b58dbbcd3c0dd82b4c53f04194d1247c6eebdaab7c610cf7d66709b3b",
+            "SHA256:6c3c624b58dbbcd3c0dd82b4c53f04194d1247c6eebdaab7c610cf7d66709b3b",
+            "sha256+:6c3c624b58dbbcd3c0dd82b4c53f04194d1247c6eebdaab7c610cf7d66709b3b",
+            "sha384:6c3c624b58dbbcd3c0dd82b4c53f04194d1247c6eebdaab7c610cf7d66709b3b",
+            "sha256:6C3C624B58DBBCD3C0DD82B4C53F04194D1247C6EEBDAAB7C610CF7D66709B3B",
+            "sha256:6c3c",
+        ] {
+            assert!(Multihash::from_oci_digest(s).is_err(), "{}", s);
+        }
+        assert_eq!(
+            Multihash::from_oci_digest("sha512:6c3c"),
+            Err(Error::InvalidDigestLength {
+                expected: 64,
+                len: 2
+            })
+        );
+    }
+}
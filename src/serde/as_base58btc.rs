@@ -0,0 +1,32 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! Serde adapter that always (de)serializes a [`crate::Multihash`] as a
+//! base58btc multibase string (e.g. "zQm..."), regardless of whether the
+//! format is human readable.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(with = "multihash::serde::as_base58btc")]
+//!     hash: Multihash,
+//! }
+//! ```
+use crate::{serde::de::MultibaseVisitor, EncodedMultihash, Multihash};
+use multibase::Base;
+use serde::{Deserializer, Serializer};
+
+/// serialize the multihash as a base58btc multibase string
+pub fn serialize<S>(mh: &Multihash, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let emh = EncodedMultihash::new(Base::Base58Btc, mh.clone());
+    serializer.serialize_str(&emh.to_string())
+}
+
+/// deserialize the multihash from a string in any multibase encoding
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Multihash, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(MultibaseVisitor)
+}
@@ -0,0 +1,31 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! Serde adapter that always (de)serializes a [`crate::Multihash`] as its
+//! binary encoding, regardless of whether the format is human readable.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(with = "multihash::serde::as_bytes")]
+//!     hash: Multihash,
+//! }
+//! ```
+use crate::{serde::de::BinaryVisitor, Multihash};
+use serde::{Deserializer, Serializer};
+
+/// serialize the multihash as its binary encoding
+pub fn serialize<S>(mh: &Multihash, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let v: Vec<u8> = mh.into();
+    serializer.serialize_bytes(v.as_slice())
+}
+
+/// deserialize the multihash from its binary encoding as bytes or as a
+/// sequence of u8
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Multihash, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_bytes(BinaryVisitor::new())
+}
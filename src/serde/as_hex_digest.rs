@@ -0,0 +1,57 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! Serde adapter that always (de)serializes a [`crate::Multihash`] as the
+//! verbose `{codec, hash}` struct with the codec name and the digest as plain
+//! lowercase hex, regardless of whether the format is human readable.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct AuditRecord {
+//!     #[serde(with = "multihash::serde::as_hex_digest")]
+//!     hash: Multihash,
+//! }
+//! ```
+use crate::Multihash;
+use multicodec::Codec;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize)]
+#[serde(rename = "multihash")]
+struct HexDigestRef<'a> {
+    codec: &'a str,
+    hash: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "multihash")]
+struct HexDigest {
+    codec: String,
+    hash: String,
+}
+
+/// serialize the multihash as a `{codec, hash}` struct with a hex digest
+pub fn serialize<S>(mh: &Multihash, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    HexDigestRef {
+        codec: mh.codec.as_str(),
+        hash: hex::encode(&mh.hash),
+    }
+    .serialize(serializer)
+}
+
+/// deserialize the multihash from a `{codec, hash}` struct with a hex digest
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Multihash, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let hd = HexDigest::deserialize(deserializer)?;
+    let codec = Codec::try_from(hd.codec.as_str())
+        .map_err(|_| Error::custom("invalid multihash codec"))?;
+    let hash = hex::decode(&hd.hash).map_err(|e| Error::custom(e.to_string()))?;
+    Ok(Multihash {
+        codec,
+        hash,
+        encoding: None,
+    })
+}
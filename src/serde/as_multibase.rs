@@ -0,0 +1,30 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! Serde adapter that always (de)serializes a [`crate::Multihash`] as a
+//! multibase encoded string using the multihash's encoding, regardless of
+//! whether the format is human readable.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(with = "multihash::serde::as_multibase")]
+//!     hash: Multihash,
+//! }
+//! ```
+use crate::{serde::de::MultibaseVisitor, Multihash};
+use serde::{Deserializer, Serializer};
+
+/// serialize the multihash as a multibase encoded string
+pub fn serialize<S>(mh: &Multihash, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_str(mh)
+}
+
+/// deserialize the multihash from a string in any multibase encoding
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Multihash, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(MultibaseVisitor)
+}
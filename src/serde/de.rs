@@ -1,21 +1,98 @@
 // SPDX-License-Idnetifier: Apache-2.0
-use crate::{mh::SIGIL, Multihash};
-use core::fmt;
+use crate::{Multihash, MultihashSet};
+use core::{fmt, marker::PhantomData};
 use multicodec::Codec;
 use multiutil::EncodedVarbytes;
 use serde::{
-    de::{Error, MapAccess, Visitor},
+    de::{Error, MapAccess, SeqAccess, Visitor},
     Deserialize, Deserializer,
 };
 
+/// Visitor for the binary encodings that accepts borrowed bytes, owned bytes
+/// and sequences of u8 so that deserializers that can't lend out bytes, such
+/// as those reading from a stream, work too
+pub(crate) struct BinaryVisitor<T>(PhantomData<T>);
+
+impl<T> BinaryVisitor<T> {
+    pub(crate) fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<'de, T> Visitor<'de> for BinaryVisitor<T>
+where
+    T: for<'a> TryFrom<&'a [u8], Error = crate::Error>,
+{
+    type Value = T;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "binary encoded bytes")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<T, E>
+    where
+        E: Error,
+    {
+        T::try_from(v).map_err(|e| Error::custom(e.to_string()))
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<T, E>
+    where
+        E: Error,
+    {
+        self.visit_bytes(v)
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<T, E>
+    where
+        E: Error,
+    {
+        self.visit_bytes(v.as_slice())
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<T, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        // don't trust the size hint for more than a small preallocation
+        let mut v = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(b) = seq.next_element::<u8>()? {
+            v.push(b);
+        }
+        self.visit_bytes(v.as_slice())
+    }
+}
+
+/// Visitor for the multibase encoded string form of a Multihash
+pub(crate) struct MultibaseVisitor;
+
+impl<'de> Visitor<'de> for MultibaseVisitor {
+    type Value = Multihash;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "multibase encoded string")
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Multihash, E>
+    where
+        E: Error,
+    {
+        from_multibase(s)
+    }
+}
+
+/// decode a Multihash from the multibase string of an EncodedMultihash,
+/// remembering the base encoding of the string
+fn from_multibase<E: Error>(s: &str) -> Result<Multihash, E> {
+    s.parse().map_err(|e: crate::Error| Error::custom(e.to_string()))
+}
+
 /// Deserialize instance of [`crate::Multihash`]
 impl<'de> Deserialize<'de> for Multihash {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        const FIELDS: &'static [&'static str] = &["codec", "hash"];
-
         #[derive(Deserialize)]
         #[serde(field_identifier, rename_all = "lowercase")]
         enum Field {
@@ -29,7 +106,14 @@ impl<'de> Deserialize<'de> for Multihash {
             type Value = Multihash;
 
             fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-                write!(fmt, "struct Multihash")
+                write!(fmt, "struct Multihash or multibase encoded string")
+            }
+
+            fn visit_str<E>(self, s: &str) -> Result<Multihash, E>
+            where
+                E: Error,
+            {
+                from_multibase(s)
             }
 
             fn visit_map<V>(self, mut map: V) -> Result<Multihash, V::Error>
@@ -38,15 +122,18 @@ impl<'de> Deserialize<'de> for Multihash {
             {
                 let mut codec = None;
                 let mut hash = None;
+                let mut encoding = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::Codec => {
                             if codec.is_some() {
                                 return Err(Error::duplicate_field("codec"));
                             }
-                            let s: &str = map.next_value()?;
+                            // owned so that readers that can't lend out
+                            // strings work too
+                            let s: String = map.next_value()?;
                             codec = Some(
-                                Codec::try_from(s)
+                                Codec::try_from(s.as_str())
                                     .map_err(|_| Error::custom("invalid multihash codec"))?,
                             );
                         }
@@ -54,22 +141,71 @@ impl<'de> Deserialize<'de> for Multihash {
                             if hash.is_some() {
                                 return Err(Error::duplicate_field("hash"));
                             }
-                            let vb: EncodedVarbytes = map.next_value()?;
+                            let s: String = map.next_value()?;
+                            let vb = EncodedVarbytes::try_from(s.as_str())
+                                .map_err(|e| Error::custom(e.to_string()))?;
+                            // remember the base encoding used for the hash
+                            encoding = Some(vb.encoding());
                             hash = Some(vb.to_inner().to_inner());
                         }
                     }
                 }
                 let codec = codec.ok_or_else(|| Error::missing_field("codec"))?;
                 let hash = hash.ok_or_else(|| Error::missing_field("hash"))?;
-                Ok(Multihash { codec, hash })
+                Ok(Multihash {
+                    codec,
+                    hash,
+                    encoding,
+                })
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            // accept either the struct or the multibase string form
+            deserializer.deserialize_any(MultihashVisitor)
+        } else {
+            deserializer.deserialize_bytes(BinaryVisitor::new())
+        }
+    }
+}
+
+/// Deserialize instance of [`crate::MultihashSet`]
+impl<'de> Deserialize<'de> for MultihashSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct MultihashSetVisitor;
+
+        impl<'de> Visitor<'de> for MultihashSetVisitor {
+            type Value = MultihashSet;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                write!(fmt, "sequence of Multihash")
+            }
+
+            fn visit_seq<V>(self, mut seq: V) -> Result<MultihashSet, V::Error>
+            where
+                V: SeqAccess<'de>,
+            {
+                let mut set = MultihashSet::new();
+                while let Some(mh) = seq.next_element::<Multihash>()? {
+                    let codec = mh.codec;
+                    if set.insert(mh).is_some() {
+                        return Err(Error::custom(format!(
+                            "duplicate hash algorithm: {}",
+                            codec.as_str()
+                        )));
+                    }
+                }
+                Ok(set)
             }
         }
 
         if deserializer.is_human_readable() {
-            deserializer.deserialize_struct(SIGIL.as_str(), FIELDS, MultihashVisitor)
+            deserializer.deserialize_seq(MultihashSetVisitor)
         } else {
-            let b: &'de [u8] = Deserialize::deserialize(deserializer)?;
-            Ok(Self::try_from(b).map_err(|e| Error::custom(e.to_string()))?)
+            deserializer.deserialize_bytes(BinaryVisitor::new())
         }
     }
 }
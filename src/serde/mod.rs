@@ -3,11 +3,17 @@
 mod de;
 mod ser;
 
+pub mod as_base58btc;
+pub mod as_bytes;
+pub mod as_hex_digest;
+pub mod as_multibase;
+
 #[cfg(test)]
 mod tests {
-    use crate::prelude::{Base, Builder, Codec, Multihash};
+    use crate::prelude::{Base, Builder, Codec, Multihash, MultihashSet};
     use multitrait::Null;
-    use serde_test::{assert_tokens, Configure, Token};
+    use multiutil::EncodingInfo;
+    use serde_test::{assert_de_tokens, assert_tokens, Configure, Token};
 
     #[test]
     fn test_serde_compact() {
@@ -122,4 +128,212 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_set_serde_json() {
+        let set1 = MultihashSet::of_bytes([Codec::Sha1, Codec::Sha2256], b"multihash").unwrap();
+        let s = serde_json::to_string(&set1).unwrap();
+        assert_eq!(s, "[{\"codec\":\"sha1\",\"hash\":\"f1488c2f11fb2ce392acb5b2986e640211c4690073e\"},{\"codec\":\"sha2-256\",\"hash\":\"f209cbc07c3f991725836a3aa2a581ca2029198aa420b9d99bc0e131d9f3e2cbe47\"}]".to_string());
+        let set2: MultihashSet = serde_json::from_str(&s).unwrap();
+        assert_eq!(set1, set2);
+    }
+
+    #[test]
+    fn test_set_serde_cbor() {
+        let set1 = MultihashSet::of_bytes([Codec::Sha1, Codec::Sha2256], b"multihash").unwrap();
+        let v = serde_cbor::to_vec(&set1).unwrap();
+        let set2: MultihashSet = serde_cbor::from_slice(&v).unwrap();
+        assert_eq!(set1, set2);
+    }
+
+    #[test]
+    fn test_serde_compact_owned_and_seq() {
+        const V: &[u8] = &[0x11, 0x14, // Codec::Sha1 and digest length
+            0x88, 0xc2, 0xf1, 0x1f, 0xb2, 0xce, 0x39, 0x2a,
+            0xcb, 0x5b, 0x29, 0x86, 0xe6, 0x40, 0x21, 0x1c,
+            0x46, 0x90, 0x07, 0x3e,
+        ];
+        let mh = Multihash::of_bytes(Codec::Sha1, b"multihash").unwrap();
+        let v: Vec<u8> = mh.clone().into();
+        assert_eq!(V, v.as_slice());
+
+        assert_de_tokens(&mh.clone().compact(), &[Token::Bytes(V)]);
+        assert_de_tokens(&mh.clone().compact(), &[Token::ByteBuf(V)]);
+
+        let mut tokens = vec![Token::Seq { len: Some(v.len()) }];
+        tokens.extend(v.iter().map(|b| Token::U8(*b)));
+        tokens.push(Token::SeqEnd);
+        assert_de_tokens(&mh.compact(), &tokens);
+    }
+
+    #[test]
+    fn test_serde_cbor_reader() {
+        let mh1 = Multihash::of_bytes(Codec::Sha2256, b"multihash").unwrap();
+        let v = serde_cbor::to_vec(&mh1).unwrap();
+        let mh2: Multihash = serde_cbor::from_reader(v.as_slice()).unwrap();
+        assert_eq!(mh1, mh2);
+
+        let set1 = MultihashSet::of_bytes([Codec::Sha1, Codec::Sha2256], b"multihash").unwrap();
+        let v = serde_cbor::to_vec(&set1).unwrap();
+        let set2: MultihashSet = serde_cbor::from_reader(v.as_slice()).unwrap();
+        assert_eq!(set1, set2);
+    }
+
+    #[test]
+    fn test_serde_json_reader() {
+        let mh1 = Multihash::of_bytes(Codec::Sha2256, b"multihash").unwrap();
+        let s = serde_json::to_string(&mh1).unwrap();
+        let mh2: Multihash = serde_json::from_reader(s.as_bytes()).unwrap();
+        assert_eq!(mh1, mh2);
+
+        let set1 = MultihashSet::of_bytes([Codec::Sha1, Codec::Sha2256], b"multihash").unwrap();
+        let s = serde_json::to_string(&set1).unwrap();
+        let set2: MultihashSet = serde_json::from_reader(s.as_bytes()).unwrap();
+        assert_eq!(set1, set2);
+    }
+
+    #[test]
+    fn test_serde_readable_multibase_string() {
+        let mh = Builder::new_from_bytes(Codec::Blake2S256, b"for great justice, move every zig!")
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_de_tokens(
+            &mh.clone().readable(),
+            &[Token::BorrowedStr(
+                "z2i3XjxBTdEn8wccxPbpSQgKveXi5jB8zUn4S9u57ZmyhQuS3bm",
+            )],
+        );
+        assert_de_tokens(
+            &mh.readable(),
+            &[Token::Str(
+                "fe0e40220642203125d59e8b93edb676fc78de9c587cf52ccc6f219032da1f377082332b0",
+            )],
+        );
+    }
+
+    #[test]
+    fn test_serde_json_multibase_string() {
+        #[derive(serde::Deserialize)]
+        struct Config {
+            hash: Multihash,
+        }
+        let c: Config = serde_json::from_str(
+            "{\"hash\": \"zQmYtUc4iTCbbfVSDNKvtQqrfyezPPnFvE33wFmutw9PBBk\"}",
+        )
+        .unwrap();
+        assert_eq!(c.hash, Multihash::of_bytes(Codec::Sha2256, b"multihash").unwrap());
+        assert!(serde_json::from_str::<Multihash>("\"zinvalid\"").is_err());
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Adapters {
+        #[serde(with = "crate::serde::as_multibase")]
+        multibase: Multihash,
+        #[serde(with = "crate::serde::as_base58btc")]
+        base58btc: Multihash,
+        #[serde(with = "crate::serde::as_hex_digest")]
+        hex_digest: Multihash,
+        #[serde(with = "crate::serde::as_bytes")]
+        bytes: Multihash,
+    }
+
+    fn adapters() -> Adapters {
+        let mh = Multihash::of_bytes(Codec::Sha2256, b"multihash").unwrap();
+        Adapters {
+            multibase: mh.clone(),
+            base58btc: mh.clone(),
+            hex_digest: mh.clone(),
+            bytes: mh,
+        }
+    }
+
+    #[test]
+    fn test_serde_adapters_json() {
+        let a1 = adapters();
+        let s = serde_json::to_string(&a1).unwrap();
+        assert_eq!(s, "{\"multibase\":\"f12209cbc07c3f991725836a3aa2a581ca2029198aa420b9d99bc0e131d9f3e2cbe47\",\"base58btc\":\"zQmYtUc4iTCbbfVSDNKvtQqrfyezPPnFvE33wFmutw9PBBk\",\"hex_digest\":{\"codec\":\"sha2-256\",\"hash\":\"9cbc07c3f991725836a3aa2a581ca2029198aa420b9d99bc0e131d9f3e2cbe47\"},\"bytes\":[18,32,156,188,7,195,249,145,114,88,54,163,170,42,88,28,162,2,145,152,170,66,11,157,153,188,14,19,29,159,62,44,190,71]}".to_string());
+        let a2: Adapters = serde_json::from_str(&s).unwrap();
+        assert_eq!(a1, a2);
+    }
+
+    #[test]
+    fn test_serde_adapters_cbor() {
+        let a1 = adapters();
+        let v = serde_cbor::to_vec(&a1).unwrap();
+        let a2: Adapters = serde_cbor::from_slice(&v).unwrap();
+        assert_eq!(a1, a2);
+        let a3: Adapters = serde_cbor::from_reader(v.as_slice()).unwrap();
+        assert_eq!(a1, a3);
+    }
+
+    #[test]
+    fn test_serde_adapter_tokens() {
+        let mh = Multihash::of_bytes(Codec::Sha1, b"multihash").unwrap();
+        let a = Adapters {
+            multibase: mh.clone(),
+            base58btc: mh.clone(),
+            hex_digest: mh.clone(),
+            bytes: mh,
+        };
+        // the same representation in compact formats as in readable ones
+        assert_tokens(
+            &a.compact(),
+            &[
+                Token::Struct { name: "Adapters", len: 4 },
+                Token::Str("multibase"),
+                Token::Str("f111488c2f11fb2ce392acb5b2986e640211c4690073e"),
+                Token::Str("base58btc"),
+                Token::Str("z5dsgvJGnvAfiR3K6HCBc4hcokSfmjj"),
+                Token::Str("hex_digest"),
+                Token::Struct { name: "multihash", len: 2 },
+                Token::Str("codec"),
+                Token::Str("sha1"),
+                Token::Str("hash"),
+                Token::Str("88c2f11fb2ce392acb5b2986e640211c4690073e"),
+                Token::StructEnd,
+                Token::Str("bytes"),
+                Token::Bytes(&[0x11, 0x14,
+                    0x88, 0xc2, 0xf1, 0x1f, 0xb2, 0xce, 0x39, 0x2a,
+                    0xcb, 0x5b, 0x29, 0x86, 0xe6, 0x40, 0x21, 0x1c,
+                    0x46, 0x90, 0x07, 0x3e,
+                ]),
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_serde_json_preserves_encoding() {
+        // a user supplied base32 hash isn't rewritten as hex
+        let s = "{\"codec\":\"sha2-256\",\"hash\":\"becolyb6d7gixewbwuovcuwa4uibjdgfkiifz3gn4byjr3hz6fs7eo\"}";
+        let mh: Multihash = serde_json::from_str(s).unwrap();
+        assert_eq!(mh, Multihash::of_bytes(Codec::Sha2256, b"multihash").unwrap());
+        assert_eq!(mh.encoding(), Base::Base32Lower);
+        assert_eq!(serde_json::to_string(&mh).unwrap(), s);
+
+        // the multibase string form remembers its base too
+        let mh: Multihash =
+            serde_json::from_str("\"zQmYtUc4iTCbbfVSDNKvtQqrfyezPPnFvE33wFmutw9PBBk\"").unwrap();
+        assert_eq!(mh.encoding(), Base::Base58Btc);
+        let a = Adapters {
+            multibase: mh.clone(),
+            base58btc: mh.clone(),
+            hex_digest: mh.clone(),
+            bytes: mh,
+        };
+        let s = serde_json::to_string(&a).unwrap();
+        assert!(s.starts_with("{\"multibase\":\"zQmYtUc4iTCbbfVSDNKvtQqrfyezPPnFvE33wFmutw9PBBk\""));
+    }
+
+    #[test]
+    fn test_serde_struct_encoding() {
+        let mh = Multihash::of_bytes(Codec::Sha1, b"multihash")
+            .unwrap()
+            .with_encoding(Base::Base58Btc);
+        let s = serde_json::to_string(&mh).unwrap();
+        let mh2: Multihash = serde_json::from_str(&s).unwrap();
+        assert_eq!(mh2.encoding(), Base::Base58Btc);
+        assert_eq!(serde_json::to_string(&mh2).unwrap(), s);
+    }
 }
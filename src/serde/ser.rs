@@ -1,7 +1,10 @@
 // SPDX-License-Idnetifier: Apache-2.0
-use crate::mh::{Multihash, SIGIL};
+use crate::{
+    mh::{Multihash, SIGIL},
+    MultihashSet,
+};
 use multiutil::{EncodingInfo, Varbytes};
-use serde::ser::{self, SerializeStruct};
+use serde::ser::{self, SerializeSeq, SerializeStruct};
 
 /// Serialize instance of [`crate::Multihash`]
 impl ser::Serialize for Multihash {
@@ -14,11 +17,30 @@ impl ser::Serialize for Multihash {
             ss.serialize_field("codec", &self.codec)?;
             ss.serialize_field(
                 "hash",
-                &Varbytes::encoded_new(Self::preferred_encoding(), self.hash.clone()),
+                &Varbytes::encoded_new(self.encoding(), self.hash.clone()),
             )?;
             ss.end()
         } else {
-            let v: Vec<u8> = self.clone().into();
+            let v: Vec<u8> = self.into();
+            serializer.serialize_bytes(v.as_slice())
+        }
+    }
+}
+
+/// Serialize instance of [`crate::MultihashSet`]
+impl ser::Serialize for MultihashSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        if serializer.is_human_readable() {
+            let mut ss = serializer.serialize_seq(Some(self.len()))?;
+            for mh in self {
+                ss.serialize_element(mh)?;
+            }
+            ss.end()
+        } else {
+            let v: Vec<u8> = self.into();
             serializer.serialize_bytes(v.as_slice())
         }
     }
@@ -0,0 +1,343 @@
+// SPDX-License-Idnetifier: Apache-2.0
+use crate::{mh::HASH_CODECS, Error, MultiHasher, Multihash};
+use multicodec::Codec;
+use multitrait::TryDecodeFrom;
+use multiutil::CodecInfo;
+
+/// The policy used when verifying data against a [`MultihashSet`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Policy {
+    /// every multihash in the set must match the data
+    All,
+    /// the multihash for the first of the trusted codecs, in order of
+    /// preference, that is in the set must match the data
+    Trusted(Vec<Codec>),
+}
+
+/// A set of multihashes of the same data, at most one per hash codec. The
+/// multihashes are kept in the order they were inserted.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MultihashSet {
+    hashes: Vec<Multihash>,
+}
+
+impl MultihashSet {
+    /// create a new, empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// hash the data once with each of the codecs and return the set of
+    /// resulting multihashes
+    pub fn of_bytes(
+        codecs: impl IntoIterator<Item = Codec>,
+        bytes: impl AsRef<[u8]>,
+    ) -> Result<Self, Error> {
+        let mut hasher = MultiHasher::new(codecs)?;
+        hasher.update(bytes.as_ref());
+        Ok(Self {
+            hashes: hasher.finalize(),
+        })
+    }
+
+    /// insert a multihash, returning the multihash it replaced with the same
+    /// codec, if any
+    pub fn insert(&mut self, mh: Multihash) -> Option<Multihash> {
+        match self.hashes.iter_mut().find(|h| h.codec() == mh.codec()) {
+            Some(h) => Some(std::mem::replace(h, mh)),
+            None => {
+                self.hashes.push(mh);
+                None
+            }
+        }
+    }
+
+    /// remove the multihash with the given codec
+    pub fn remove(&mut self, codec: Codec) -> Option<Multihash> {
+        let i = self.hashes.iter().position(|h| h.codec() == codec)?;
+        Some(self.hashes.remove(i))
+    }
+
+    /// get the multihash with the given codec
+    pub fn get(&self, codec: Codec) -> Option<&Multihash> {
+        self.hashes.iter().find(|h| h.codec() == codec)
+    }
+
+    /// true if the set has a multihash with the given codec
+    pub fn contains(&self, codec: Codec) -> bool {
+        self.get(codec).is_some()
+    }
+
+    /// the codecs of the multihashes in the set
+    pub fn codecs(&self) -> Vec<Codec> {
+        self.hashes.iter().map(|h| h.codec()).collect()
+    }
+
+    /// the number of multihashes in the set
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// true if the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// iterate over the multihashes in the set
+    pub fn iter(&self) -> std::slice::Iter<'_, Multihash> {
+        self.hashes.iter()
+    }
+
+    /// decode a set that must take up all of the bytes, unlike
+    /// `TryFrom<&[u8]>` which ignores anything after the set
+    pub fn decode_exact(bytes: &[u8]) -> Result<Self, Error> {
+        let (set, ptr) = Self::try_decode_from(bytes)?;
+        if !ptr.is_empty() {
+            return Err(Error::TrailingData {
+                offset: bytes.len() - ptr.len(),
+                len: ptr.len(),
+            });
+        }
+        Ok(set)
+    }
+
+    /// verify the data against the multihashes in the set under the policy. a
+    /// multihash with a codec that isn't one of the supported hash codecs
+    /// can't be recomputed, so checking it fails with a hash mismatch
+    pub fn verify(&self, bytes: impl AsRef<[u8]>, policy: &Policy) -> Result<(), Error> {
+        let expected: Vec<&Multihash> = match policy {
+            Policy::All => {
+                if self.is_empty() {
+                    return Err(Error::MissingHash);
+                }
+                self.hashes.iter().collect()
+            }
+            Policy::Trusted(codecs) => {
+                let mh = codecs
+                    .iter()
+                    .find_map(|c| self.get(*c))
+                    .ok_or(Error::NoTrustedHash)?;
+                vec![mh]
+            }
+        };
+
+        if let Some(mh) = expected.iter().find(|mh| !HASH_CODECS.contains(&mh.codec())) {
+            return Err(Error::HashMismatch(mh.codec()));
+        }
+
+        // hash the data once with all of the codecs being checked
+        let mut hasher = MultiHasher::new(expected.iter().map(|h| h.codec()))?;
+        hasher.update(bytes.as_ref());
+        for (e, a) in expected.iter().zip(hasher.finalize().iter()) {
+            if *e != a {
+                return Err(Error::HashMismatch(e.codec()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<Multihash> for MultihashSet {
+    fn from_iter<I: IntoIterator<Item = Multihash>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for mh in iter {
+            set.insert(mh);
+        }
+        set
+    }
+}
+
+impl IntoIterator for MultihashSet {
+    type Item = Multihash;
+    type IntoIter = std::vec::IntoIter<Multihash>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.hashes.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a MultihashSet {
+    type Item = &'a Multihash;
+    type IntoIter = std::slice::Iter<'a, Multihash>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.hashes.iter()
+    }
+}
+
+impl From<&MultihashSet> for Vec<u8> {
+    fn from(set: &MultihashSet) -> Self {
+        let mut buf = unsigned_varint::encode::usize_buffer();
+        let count = unsigned_varint::encode::usize(set.len(), &mut buf);
+        let len = count.len() + set.iter().map(|mh| mh.encoded_len()).sum::<usize>();
+        let mut v = Vec::with_capacity(len);
+        // add in the count of multihashes
+        v.extend_from_slice(count);
+        // add in each of the multihashes
+        for mh in set {
+            mh.write_to(&mut v)
+                .expect("writing to a Vec never fails");
+        }
+        v
+    }
+}
+
+impl From<MultihashSet> for Vec<u8> {
+    fn from(set: MultihashSet) -> Self {
+        (&set).into()
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for MultihashSet {
+    type Error = Error;
+
+    fn try_from(s: &'a [u8]) -> Result<Self, Self::Error> {
+        let (set, _) = Self::try_decode_from(s)?;
+        Ok(set)
+    }
+}
+
+impl<'a> TryDecodeFrom<'a> for MultihashSet {
+    type Error = Error;
+
+    fn try_decode_from(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Self::Error> {
+        // decode the count of multihashes
+        let (count, mut ptr) = unsigned_varint::decode::usize(bytes).map_err(|e| match e {
+            unsigned_varint::decode::Error::Insufficient => Error::Truncated { offset: 0 },
+            _ => Error::InvalidVarint { offset: 0 },
+        })?;
+        let mut set = Self::new();
+        for _ in 0..count {
+            let (mh, p) = Multihash::decode_at(ptr, bytes.len() - ptr.len())?;
+            let codec = mh.codec();
+            if set.insert(mh).is_some() {
+                return Err(Error::DuplicateHash(codec));
+            }
+            ptr = p;
+        }
+        Ok((set, ptr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = b"for great justice, move every zig!";
+
+    #[test]
+    fn test_verify_all() {
+        let set = MultihashSet::of_bytes([Codec::Sha1, Codec::Sha2256], DATA).unwrap();
+        assert_eq!(set.codecs(), vec![Codec::Sha1, Codec::Sha2256]);
+        assert!(set.verify(DATA, &Policy::All).is_ok());
+        assert!(matches!(
+            set.verify(b"move every zig!", &Policy::All),
+            Err(Error::HashMismatch(Codec::Sha1))
+        ));
+        assert!(matches!(
+            MultihashSet::new().verify(DATA, &Policy::All),
+            Err(Error::MissingHash)
+        ));
+    }
+
+    #[test]
+    fn test_verify_trusted() {
+        // a set with a bad sha1 entry still verifies when sha2-256 is trusted
+        let mut set = MultihashSet::of_bytes([Codec::Sha2256], DATA).unwrap();
+        set.insert(Multihash::of_bytes(Codec::Sha1, b"something else").unwrap());
+        let policy = Policy::Trusted(vec![Codec::Sha2256, Codec::Sha1]);
+        assert!(set.verify(DATA, &policy).is_ok());
+        assert!(set.verify(DATA, &Policy::All).is_err());
+
+        // only sha1 is trusted
+        let policy = Policy::Trusted(vec![Codec::Sha1]);
+        assert!(matches!(
+            set.verify(DATA, &policy),
+            Err(Error::HashMismatch(Codec::Sha1))
+        ));
+
+        // nothing trusted is in the set
+        let policy = Policy::Trusted(vec![Codec::Sha3512]);
+        assert!(matches!(
+            set.verify(DATA, &policy),
+            Err(Error::NoTrustedHash)
+        ));
+    }
+
+    #[test]
+    fn test_verify_unsupported() {
+        // an identity multihash decoded from elsewhere can't be recomputed
+        let mut set = MultihashSet::of_bytes([Codec::Sha2256], DATA).unwrap();
+        set.insert(Multihash::try_from(&[0x00, 0x02, 0x6d, 0x68][..]).unwrap());
+        assert!(matches!(
+            set.verify(DATA, &Policy::All),
+            Err(Error::HashMismatch(Codec::Identity))
+        ));
+        let policy = Policy::Trusted(vec![Codec::Identity, Codec::Sha2256]);
+        assert!(matches!(
+            set.verify(DATA, &policy),
+            Err(Error::HashMismatch(Codec::Identity))
+        ));
+        let policy = Policy::Trusted(vec![Codec::Sha2256, Codec::Identity]);
+        assert!(set.verify(DATA, &policy).is_ok());
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set = MultihashSet::new();
+        assert!(set.insert(Multihash::of_bytes(Codec::Sha1, DATA).unwrap()).is_none());
+        assert!(set.insert(Multihash::of_bytes(Codec::Sha1, DATA).unwrap()).is_some());
+        assert_eq!(set.len(), 1);
+        assert!(set.contains(Codec::Sha1));
+        assert!(set.remove(Codec::Sha1).is_some());
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let set1 =
+            MultihashSet::of_bytes([Codec::Sha1, Codec::Sha2256, Codec::Blake3], DATA).unwrap();
+        let v: Vec<u8> = set1.clone().into();
+        assert_eq!(v[0], 3);
+        let set2 = MultihashSet::try_from(v.as_slice()).unwrap();
+        assert_eq!(set1, set2);
+    }
+
+    #[test]
+    fn test_binary_errors() {
+        let set = MultihashSet::of_bytes([Codec::Sha1, Codec::Sha2256], DATA).unwrap();
+        let v: Vec<u8> = set.into();
+        // the offset is of the digest of the second multihash in the set
+        assert_eq!(
+            MultihashSet::try_from(&v[..30]),
+            Err(Error::TruncatedDigest {
+                offset: 25,
+                expected: 32,
+                available: 5
+            })
+        );
+        let mut t = v.clone();
+        t.push(0);
+        assert_eq!(
+            MultihashSet::decode_exact(t.as_slice()),
+            Err(Error::TrailingData {
+                offset: v.len(),
+                len: 1
+            })
+        );
+        assert!(MultihashSet::try_from(t.as_slice()).is_ok());
+        assert_eq!(MultihashSet::try_from(&[][..]), Err(Error::Truncated { offset: 0 }));
+    }
+
+    #[test]
+    fn test_binary_duplicate() {
+        let mh = Multihash::of_bytes(Codec::Sha1, DATA).unwrap();
+        let mut v = vec![2];
+        v.append(&mut mh.clone().into());
+        v.append(&mut mh.into());
+        assert!(matches!(
+            MultihashSet::try_from(v.as_slice()),
+            Err(Error::DuplicateHash(Codec::Sha1))
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+// SPDX-License-Idnetifier: Apache-2.0
+//! Conversion between [`Multihash`] and W3C Subresource Integrity (SRI)
+//! metadata such as `sha384-<base64>` used in `integrity` attributes.
+use crate::{Error, Multihash};
+use multibase::Base;
+use multicodec::Codec;
+use multiutil::CodecInfo;
+
+/// the SRI hash algorithms, from weakest to strongest
+pub const SRI_ALGORITHMS: [(Codec, &str); 3] = [
+    (Codec::Sha2256, "sha256"),
+    (Codec::Sha2384, "sha384"),
+    (Codec::Sha2512, "sha512"),
+];
+
+/// the SRI algorithm name of the codec
+pub fn algorithm_name(codec: Codec) -> Option<&'static str> {
+    SRI_ALGORITHMS
+        .iter()
+        .find(|(c, _)| *c == codec)
+        .map(|(_, n)| *n)
+}
+
+/// the codec of the SRI algorithm name
+pub fn algorithm_codec(name: &str) -> Option<Codec> {
+    SRI_ALGORITHMS
+        .iter()
+        .find(|(_, n)| n.eq_ignore_ascii_case(name))
+        .map(|(c, _)| *c)
+}
+
+/// the strength of the codec, the position in the SRI algorithm list
+fn strength(codec: Codec) -> usize {
+    SRI_ALGORITHMS
+        .iter()
+        .position(|(c, _)| *c == codec)
+        .unwrap_or(0)
+}
+
+impl Multihash {
+    /// the SRI metadata of this multihash, e.g. `sha384-<base64>`. SRI has no
+    /// way to say a digest is truncated so a truncated digest is an error
+    pub fn to_sri(&self) -> Result<String, Error> {
+        let name = algorithm_name(self.codec).ok_or(Error::UnsupportedHash(self.codec))?;
+        self.check_full_digest()?;
+        Ok(format!("{}-{}", name, Base::Base64Pad.encode(&self.hash)))
+    }
+
+    /// parse a single SRI metadata token, any options after a `?` are ignored
+    pub fn from_sri(s: &str) -> Result<Self, Error> {
+        let s = s.split('?').next().unwrap_or_default();
+        let (name, b64) = s
+            .split_once('-')
+            .ok_or_else(|| Error::InvalidSri(format!("missing algorithm in '{}'", s)))?;
+        let codec = algorithm_codec(name)
+            .ok_or_else(|| Error::InvalidSri(format!("unsupported algorithm '{}'", name)))?;
+        // accept digests with or without the padding
+        let digest = Base::Base64
+            .decode(b64.trim_end_matches('='))
+            .map_err(|e| Error::InvalidSri(e.to_string()))?;
+        Self::from_digest(codec, digest)
+    }
+}
+
+/// parse an `integrity` attribute of whitespace separated SRI metadata,
+/// returning the multihashes with the strongest algorithm. the content matches
+/// the attribute if it matches any of them. tokens that are malformed or use
+/// unsupported algorithms are skipped so the result may be empty
+pub fn parse_integrity(attr: &str) -> Vec<Multihash> {
+    let mhs: Vec<Multihash> = attr
+        .split_ascii_whitespace()
+        .filter_map(|t| Multihash::from_sri(t).ok())
+        .collect();
+    let strongest = mhs.iter().map(|mh| strength(mh.codec())).max();
+    mhs.into_iter()
+        .filter(|mh| Some(strength(mh.codec())) == strongest)
+        .collect()
+}
+
+/// format the multihashes as an `integrity` attribute value
+pub fn format_integrity<'a>(
+    mhs: impl IntoIterator<Item = &'a Multihash>,
+) -> Result<String, Error> {
+    let tokens = mhs
+        .into_iter()
+        .map(|mh| mh.to_sri())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tokens.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = b"alert('Hello, world.');";
+    const SHA384: &str =
+        "sha384-H8BRh8j48O9oYatfu5AZzq6A9RINhZO5H16dQZngK7T62em8MUt1FLm52t+eX6xO";
+
+    #[test]
+    fn test_sri_roundtrip() {
+        let mh = Multihash::of_bytes(Codec::Sha2384, DATA).unwrap();
+        assert_eq!(mh.to_sri().unwrap(), SHA384);
+        assert_eq!(Multihash::from_sri(SHA384).unwrap(), mh);
+        // options and missing padding are accepted
+        let s = format!("{}?foo", SHA384.trim_end_matches('='));
+        assert_eq!(Multihash::from_sri(&s).unwrap(), mh);
+    }
+
+    #[test]
+    fn test_sri_errors() {
+        let mh = Multihash::of_bytes(Codec::Blake3, DATA).unwrap();
+        assert_eq!(mh.to_sri(), Err(Error::UnsupportedHash(Codec::Blake3)));
+        assert!(matches!(
+            Multihash::from_sri("md5-AAAA"),
+            Err(Error::InvalidSri(_))
+        ));
+        assert!(matches!(
+            Multihash::from_sri("sha256"),
+            Err(Error::InvalidSri(_))
+        ));
+        assert_eq!(
+            Multihash::from_sri("sha256-AAAA"),
+            Err(Error::InvalidDigestLength {
+                expected: 32,
+                len: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_sri_truncated() {
+        let mh = Multihash::of_bytes(Codec::Sha2384, DATA).unwrap();
+        let truncated = Multihash {
+            codec: mh.codec(),
+            hash: mh.as_ref()[..24].to_vec(),
+            encoding: None,
+        };
+        let err = Err(Error::InvalidDigestLength {
+            expected: 48,
+            len: 24,
+        });
+        assert_eq!(truncated.to_sri(), err);
+        assert_eq!(format_integrity([&mh, &truncated]), err);
+    }
+
+    #[test]
+    fn test_parse_integrity() {
+        let mhs: Vec<Multihash> = [Codec::Sha2256, Codec::Sha2512, Codec::Sha2384]
+            .iter()
+            .map(|c| Multihash::of_bytes(*c, DATA).unwrap())
+            .collect();
+        let attr = format!("md5-AAAA {} bogus", format_integrity(&mhs).unwrap());
+        assert_eq!(parse_integrity(&attr), vec![mhs[1].clone()]);
+        assert!(parse_integrity("md5-AAAA").is_empty());
+    }
+}
@@ -0,0 +1,289 @@
+// SPDX-License-Idnetifier: Apache-2.0
+use crate::{
+    mh::{decode_codec, decode_length},
+    Error, Multihash,
+};
+use std::io::{ErrorKind, Read, Write};
+
+/// the maximum length of a varint encoded u64
+const MAX_VARINT_LEN: usize = 10;
+
+/// Iterator over back-to-back binary encoded multihashes read from a stream
+/// until the end of the stream. The reader is read one byte at a time while
+/// decoding the varints so wrap unbuffered readers in a `BufReader`.
+///
+/// A stream that ends between two multihashes ends the iteration cleanly. A
+/// stream that ends in the middle of a multihash produces the same errors as
+/// decoding from a slice: an [`Error::Truncated`] with the offset of the
+/// codec or length varint that was cut short, or an
+/// [`Error::TruncatedDigest`] with the offset of the digest. The iteration
+/// stops after the first error.
+pub struct MultihashReader<R: Read> {
+    reader: R,
+    offset: usize,
+    done: bool,
+}
+
+impl<R: Read> MultihashReader<R> {
+    /// create a new reader over the stream
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            offset: 0,
+            done: false,
+        }
+    }
+
+    /// the number of bytes consumed from the stream so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// get back the inner reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// read a single byte, returning None at the end of the stream
+    fn read_byte(&mut self) -> Result<Option<u8>, Error> {
+        let mut b = [0u8; 1];
+        loop {
+            match self.reader.read(&mut b) {
+                Ok(0) => return Ok(None),
+                Ok(_) => {
+                    self.offset += 1;
+                    return Ok(Some(b[0]));
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// read the bytes of a varint into the buffer, the first byte is given,
+    /// returning the number of bytes in the varint
+    fn read_varint(
+        &mut self,
+        first: u8,
+        buf: &mut [u8; MAX_VARINT_LEN],
+        start: usize,
+    ) -> Result<usize, Error> {
+        buf[0] = first;
+        let mut len = 1;
+        while buf[len - 1] & 0x80 != 0 && len < MAX_VARINT_LEN {
+            buf[len] = self
+                .read_byte()?
+                .ok_or(Error::Truncated { offset: start })?;
+            len += 1;
+        }
+        Ok(len)
+    }
+
+    /// read the next multihash, returning None at a clean end of stream
+    fn read_multihash(&mut self) -> Result<Option<Multihash>, Error> {
+        let start = self.offset;
+        let mut buf = [0u8; MAX_VARINT_LEN];
+
+        // read the codec, the end of the stream here is a clean end
+        let first = match self.read_byte()? {
+            Some(b) => b,
+            None => return Ok(None),
+        };
+        let len = self.read_varint(first, &mut buf, start)?;
+        let (codec, _) = decode_codec(&buf[..len], start)?;
+
+        // read the length of the hash
+        let len_offset = self.offset;
+        let first = self
+            .read_byte()?
+            .ok_or(Error::Truncated { offset: len_offset })?;
+        let len = self.read_varint(first, &mut buf, len_offset)?;
+        let (hash_len, _) = decode_length(&buf[..len], len_offset)?;
+
+        // read the hash without trusting the length for the allocation
+        let digest_offset = self.offset;
+        let mut hash = Vec::with_capacity(hash_len.min(1024));
+        let n = self
+            .reader
+            .by_ref()
+            .take(hash_len as u64)
+            .read_to_end(&mut hash)?;
+        self.offset += n;
+        if n < hash_len {
+            return Err(Error::TruncatedDigest {
+                offset: digest_offset,
+                expected: hash_len,
+                available: n,
+            });
+        }
+
+        Ok(Some(Multihash {
+            codec,
+            hash,
+            encoding: None,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for MultihashReader<R> {
+    type Item = Result<Multihash, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_multihash() {
+            Ok(Some(mh)) => Some(Ok(mh)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<R: Read> std::iter::FusedIterator for MultihashReader<R> {}
+
+/// Writes back-to-back binary encoded multihashes to a stream so that they
+/// can be read back with a [`MultihashReader`]
+pub struct MultihashWriter<W: Write> {
+    writer: W,
+    offset: usize,
+}
+
+impl<W: Write> MultihashWriter<W> {
+    /// create a new writer over the stream
+    pub fn new(writer: W) -> Self {
+        Self { writer, offset: 0 }
+    }
+
+    /// the number of bytes written to the stream so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// write a multihash, returning the offset it was written at
+    pub fn write(&mut self, mh: &Multihash) -> Result<usize, Error> {
+        let offset = self.offset;
+        self.offset += mh.write_to(&mut self.writer)?;
+        Ok(offset)
+    }
+
+    /// flush the inner writer
+    pub fn flush(&mut self) -> Result<(), Error> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// get back the inner writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mh::HASH_CODECS;
+    use multicodec::Codec;
+
+    fn multihashes() -> Vec<Multihash> {
+        HASH_CODECS
+            .iter()
+            .map(|c| Multihash::of_bytes(*c, b"multihash").unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mhs = multihashes();
+        let mut w = MultihashWriter::new(Vec::default());
+        for mh in &mhs {
+            w.write(mh).unwrap();
+        }
+        w.flush().unwrap();
+        let len = w.offset();
+        let v = w.into_inner();
+        assert_eq!(v.len(), len);
+
+        let mut r = MultihashReader::new(v.as_slice());
+        let mhs2: Vec<Multihash> = r.by_ref().map(|mh| mh.unwrap()).collect();
+        assert_eq!(mhs, mhs2);
+        assert_eq!(r.offset(), len);
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut r = MultihashReader::new(&[][..]);
+        assert!(r.next().is_none());
+    }
+
+    #[test]
+    fn test_unknown_codec() {
+        let mut v = Vec::default();
+        let mh = Multihash::of_bytes(Codec::Sha1, b"multihash").unwrap();
+        mh.write_to(&mut v).unwrap();
+        let start = v.len();
+        v.extend_from_slice(&[0xff, 0xff, 0x03, 0x00]);
+        let mut r = MultihashReader::new(v.as_slice());
+        assert_eq!(r.next(), Some(Ok(mh)));
+        assert_eq!(
+            r.next(),
+            Some(Err(Error::UnknownCodec {
+                offset: start,
+                code: 0xffff
+            }))
+        );
+        assert_eq!(r.next(), None);
+    }
+
+    #[test]
+    fn test_truncated() {
+        let mhs = multihashes();
+        let mut v = Vec::default();
+        mhs[0].write_to(&mut v).unwrap();
+        let start = v.len();
+        mhs[1].write_to(&mut v).unwrap();
+
+        // truncate at every point inside of the second multihash, the errors
+        // match those from decoding the same bytes from a slice
+        for end in start + 1..v.len() {
+            let mut r = MultihashReader::new(&v[..end]);
+            assert_eq!(r.next().unwrap().unwrap(), mhs[0]);
+            let err = Multihash::decode_at(&v[start..end], start).unwrap_err();
+            assert!(matches!(
+                err,
+                Error::Truncated { .. } | Error::TruncatedDigest { .. }
+            ));
+            assert_eq!(r.next(), Some(Err(err)));
+            assert!(r.next().is_none());
+        }
+    }
+
+    #[test]
+    fn test_truncated_offsets() {
+        let mh = Multihash::of_bytes(Codec::Blake2S256, b"multihash").unwrap();
+        let mut v = Vec::default();
+        mh.write_to(&mut v).unwrap();
+
+        // in the codec varint
+        let mut r = MultihashReader::new(&v[..2]);
+        assert_eq!(r.next(), Some(Err(Error::Truncated { offset: 0 })));
+        // before the length
+        let mut r = MultihashReader::new(&v[..3]);
+        assert_eq!(r.next(), Some(Err(Error::Truncated { offset: 3 })));
+        // in the digest
+        let mut r = MultihashReader::new(&v[..20]);
+        assert_eq!(
+            r.next(),
+            Some(Err(Error::TruncatedDigest {
+                offset: 4,
+                expected: 32,
+                available: 16
+            }))
+        );
+    }
+}